@@ -10,6 +10,8 @@ use std::borrow::Cow;
 
 /// Default value for the color flag.
 const DEFAULT_COLOR_ENABLED: bool = true;
+/// Default value for the span subscriber flag.
+const DEFAULT_SPAN_SUBSCRIBER_ENABLED: bool = false;
 /// Default name for an output.
 const DEFAULT_OUTPUT_NAME: &str = LOGGER_STDOUT_NAME;
 /// Default log level for an output.
@@ -82,6 +84,8 @@ pub struct LoggerOutputConfig {
 pub struct LoggerConfigBuilder {
     /// Color flag of the logger.
     color_enabled: Option<bool>,
+    /// Span subscriber flag of the logger.
+    span_subscriber_enabled: Option<bool>,
     /// Outputs of the logger.
     outputs: Option<Vec<LoggerOutputConfigBuilder>>,
 }
@@ -93,6 +97,12 @@ impl LoggerConfigBuilder {
         self
     }
 
+    /// Sets whether the logger installs a `tracing` subscriber to render the context of spans, disabled by default.
+    pub fn span_subscriber_enabled(mut self, enabled: bool) -> Self {
+        self.span_subscriber_enabled.replace(enabled);
+        self
+    }
+
     /// Adds an output builder to the logger builder.
     pub fn with_output(mut self, output: LoggerOutputConfigBuilder) -> Self {
         self.outputs.get_or_insert_with(Vec::new).push(output);
@@ -122,6 +132,7 @@ impl LoggerConfigBuilder {
 
         LoggerConfig {
             color_enabled: self.color_enabled.unwrap_or(DEFAULT_COLOR_ENABLED),
+            span_subscriber_enabled: self.span_subscriber_enabled.unwrap_or(DEFAULT_SPAN_SUBSCRIBER_ENABLED),
             outputs,
         }
     }
@@ -132,6 +143,8 @@ impl LoggerConfigBuilder {
 pub struct LoggerConfig {
     /// Color flag of the logger.
     pub(crate) color_enabled: bool,
+    /// Span subscriber flag of the logger.
+    pub(crate) span_subscriber_enabled: bool,
     /// Outputs of the logger.
     pub(crate) outputs: Vec<LoggerOutputConfig>,
 }
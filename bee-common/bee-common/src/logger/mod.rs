@@ -4,8 +4,10 @@
 //! A logger backend for the `log` crate.
 
 mod config;
+mod span;
 
 pub use config::{LoggerConfig, LoggerConfigBuilder, LoggerOutputConfig, LoggerOutputConfigBuilder};
+pub use span::{span_context, span_subscriber, SpanContextLayer};
 
 use fern::{
     colors::{Color, ColoredLevelConfig},
@@ -26,15 +28,19 @@ pub enum Error {
     /// Initializing the logger backend failed.
     #[error("Initializing the logger backend failed.")]
     InitializationFailed,
+    /// Installing the span subscriber failed, as a global `tracing` subscriber is already installed.
+    #[error("Installing the span subscriber failed: a global tracing subscriber is already installed.")]
+    SpanSubscriberFailed,
 }
 
 macro_rules! log_format {
     ($target:expr, $level:expr, $message:expr) => {
         format_args!(
-            "{}[{}][{}] {}",
+            "{}[{}][{}] {}{}",
             chrono::Local::now().format("[%Y-%m-%d][%H:%M:%S]"),
             $target,
             $level,
+            span_context().map(|context| context + ": ").unwrap_or_default(),
             $message
         )
     };
@@ -42,6 +48,10 @@ macro_rules! log_format {
 
 /// Initializes a `fern` logger backend for the `log` crate.
 ///
+/// Log lines are rendered with the context of the `tracing` spans they are emitted in, see [`span_context`], which
+/// requires a [`span_subscriber`]. If enabled in the configuration, it is installed as the global `tracing` subscriber,
+/// which fails if there is one already.
+///
 /// # Arguments
 ///
 /// * `config`  -   Logger configuration
@@ -72,7 +82,8 @@ pub fn logger_init(config: LoggerConfig) -> Result<(), Error> {
             let target_filters = output.target_filters;
             dispatch = dispatch.filter(move |metadata| {
                 let target = metadata.target().to_lowercase();
-                target_filters.iter().any(|f| target.contains(f))
+                let context = span_context().unwrap_or_default().to_lowercase();
+                target_filters.iter().any(|f| target.contains(f) || context.contains(f))
             });
         }
 
@@ -88,5 +99,9 @@ pub fn logger_init(config: LoggerConfig) -> Result<(), Error> {
 
     logger.apply().map_err(|_| Error::InitializationFailed)?;
 
+    if config.span_subscriber_enabled {
+        tracing::subscriber::set_global_default(span_subscriber()).map_err(|_| Error::SpanSubscriberFailed)?;
+    }
+
     Ok(())
 }
@@ -0,0 +1,144 @@
+// Copyright 2020-2021 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+use tracing::{
+    field::{Field, Visit},
+    span, Event, Level, Subscriber,
+};
+use tracing_subscriber::{
+    layer::{Context, Layer, SubscriberExt},
+    registry::{LookupSpan, Registry, Scope},
+};
+
+use std::{
+    cell::RefCell,
+    fmt::{self, Write},
+};
+
+thread_local! {
+    // The span context of the `tracing` event being forwarded to the logger, as the subscriber can't be reached while
+    // it handles the event.
+    static EVENT_CONTEXT: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
+// The fields of a span, rendered when the span is created and updated when they are recorded.
+struct SpanFields(String);
+
+// Renders fields as `name=value` pairs separated by spaces, the `message` field of events coming first and unnamed.
+struct FieldsVisitor<'a>(&'a mut String);
+
+impl FieldsVisitor<'_> {
+    fn write(&mut self, field: &Field, value: fmt::Arguments<'_>) {
+        // Writing to a `String` can't fail.
+        if field.name() == "message" {
+            self.0.insert_str(0, &value.to_string());
+        } else {
+            if !self.0.is_empty() {
+                self.0.push(' ');
+            }
+            write!(self.0, "{}={}", field.name(), value).unwrap();
+        }
+    }
+}
+
+impl Visit for FieldsVisitor<'_> {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.write(field, format_args!("{}", value));
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        self.write(field, format_args!("{:?}", value));
+    }
+}
+
+/// A `tracing` layer that keeps the fields of spans for [`span_context`] and forwards `tracing` events to the `log`
+/// logger.
+pub struct SpanContextLayer;
+
+impl<S: Subscriber + for<'a> LookupSpan<'a>> Layer<S> for SpanContextLayer {
+    fn on_new_span(&self, attrs: &span::Attributes<'_>, id: &span::Id, ctx: Context<'_, S>) {
+        let mut fields = String::new();
+
+        attrs.record(&mut FieldsVisitor(&mut fields));
+
+        if let Some(span) = ctx.span(id) {
+            span.extensions_mut().insert(SpanFields(fields));
+        }
+    }
+
+    fn on_record(&self, id: &span::Id, values: &span::Record<'_>, ctx: Context<'_, S>) {
+        if let Some(span) = ctx.span(id) {
+            if let Some(SpanFields(fields)) = span.extensions_mut().get_mut::<SpanFields>() {
+                values.record(&mut FieldsVisitor(fields));
+            }
+        }
+    }
+
+    fn on_event(&self, event: &Event<'_>, ctx: Context<'_, S>) {
+        let metadata = event.metadata();
+        let level = match *metadata.level() {
+            Level::ERROR => log::Level::Error,
+            Level::WARN => log::Level::Warn,
+            Level::INFO => log::Level::Info,
+            Level::DEBUG => log::Level::Debug,
+            Level::TRACE => log::Level::Trace,
+        };
+        let log_metadata = log::Metadata::builder().level(level).target(metadata.target()).build();
+        let logger = log::logger();
+
+        if level > log::max_level() || !logger.enabled(&log_metadata) {
+            return;
+        }
+
+        let mut message = String::new();
+        event.record(&mut FieldsVisitor(&mut message));
+
+        let context = ctx.event_scope(event).map(render);
+        EVENT_CONTEXT.with(|event_context| *event_context.borrow_mut() = context);
+        logger.log(
+            &log::Record::builder()
+                .metadata(log_metadata)
+                .args(format_args!("{}", message))
+                .module_path(metadata.module_path())
+                .file(metadata.file())
+                .line(metadata.line())
+                .build(),
+        );
+        EVENT_CONTEXT.with(|event_context| event_context.borrow_mut().take());
+    }
+}
+
+/// Creates a `tracing` subscriber that keeps track of spans, for log lines to be rendered with their span context, and
+/// forwards `tracing` events to the `log` logger.
+pub fn span_subscriber() -> impl Subscriber + Send + Sync {
+    Registry::default().with(SpanContextLayer)
+}
+
+/// Renders the spans the current thread is in, from the outermost to the innermost one, e.g.
+/// `start{worker=bee_gossip::Gossip}`.
+///
+/// Returns `None` outside of any span or if the current `tracing` subscriber isn't built on a
+/// [`Registry`](tracing_subscriber::registry::Registry), like the one returned by [`span_subscriber`].
+pub fn span_context() -> Option<String> {
+    if let Some(context) = EVENT_CONTEXT.with(|context| context.borrow().clone()) {
+        return Some(context);
+    }
+
+    tracing::dispatcher::get_default(|dispatch| {
+        let registry = dispatch.downcast_ref::<Registry>()?;
+        let current = registry.current_span();
+
+        Some(render(registry.span(current.id()?)?.scope()))
+    })
+}
+
+fn render<'a, R: LookupSpan<'a>>(scope: Scope<'a, R>) -> String {
+    scope
+        .from_root()
+        .map(|span| match span.extensions().get::<SpanFields>() {
+            Some(SpanFields(fields)) if !fields.is_empty() => format!("{}{{{}}}", span.name(), fields),
+            _ => span.name().to_owned(),
+        })
+        .collect::<Vec<_>>()
+        .join(":")
+}
@@ -0,0 +1,66 @@
+// Copyright 2020-2021 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+use bee_common::logger::{span_context, span_subscriber};
+
+use tracing::info_span;
+
+use std::sync::Mutex;
+
+static RECORDS: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+struct Recorder;
+
+impl log::Log for Recorder {
+    fn enabled(&self, _metadata: &log::Metadata) -> bool {
+        true
+    }
+
+    fn log(&self, record: &log::Record) {
+        RECORDS.lock().unwrap().push(format!(
+            "[{}][{}] {}{}",
+            record.target(),
+            record.level(),
+            span_context().map(|context| context + ": ").unwrap_or_default(),
+            record.args()
+        ));
+    }
+
+    fn flush(&self) {}
+}
+
+#[test]
+fn span_context_and_events() {
+    log::set_logger(&Recorder).unwrap();
+    log::set_max_level(log::LevelFilter::Debug);
+
+    assert_eq!(span_context(), None);
+
+    tracing::subscriber::with_default(span_subscriber(), || {
+        assert_eq!(span_context(), None);
+
+        let worker = info_span!("start", worker = "bee_gossip::Gossip");
+        let _worker = worker.enter();
+        assert_eq!(span_context(), Some("start{worker=bee_gossip::Gossip}".to_owned()));
+
+        let task = info_span!("task", id = 3, peer = tracing::field::Empty);
+        let _task = task.enter();
+        task.record("peer", "alice");
+        assert_eq!(
+            span_context(),
+            Some("start{worker=bee_gossip::Gossip}:task{id=3 peer=alice}".to_owned())
+        );
+
+        log::info!("from log");
+        tracing::debug!(count = 2, "from tracing");
+        tracing::trace!("filtered out");
+    });
+
+    assert_eq!(
+        *RECORDS.lock().unwrap(),
+        vec![
+            "[logger][INFO] start{worker=bee_gossip::Gossip}:task{id=3 peer=alice}: from log",
+            "[logger][DEBUG] start{worker=bee_gossip::Gossip}:task{id=3 peer=alice}: from tracing count=2",
+        ]
+    );
+}
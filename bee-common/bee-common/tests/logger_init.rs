@@ -0,0 +1,13 @@
+// Copyright 2020-2021 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+use bee_common::logger::{logger_init, Error, LoggerConfig};
+
+#[test]
+fn span_subscriber_already_installed() {
+    tracing::subscriber::set_global_default(tracing::subscriber::NoSubscriber::default()).unwrap();
+
+    let config = LoggerConfig::build().span_subscriber_enabled(true).finish();
+
+    assert!(matches!(logger_init(config), Err(Error::SpanSubscriberFailed)));
+}
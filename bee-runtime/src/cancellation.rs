@@ -0,0 +1,145 @@
+// Copyright 2020-2021 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+//! A module that provides hierarchical cancellation tokens.
+//!
+//! Cancelling a token cancels all of its descendants, which allows a node to cancel all of its workers, and a worker
+//! to cancel all of its tasks, at once:
+//! ```ignore
+//! let node = CancellationToken::new();
+//! let worker = node.child();
+//! let task = worker.child();
+//!
+//! node.cancel();
+//! assert!(task.is_cancelled());
+//! ```
+
+use futures::{
+    channel::oneshot,
+    future::{FusedFuture, Future},
+    task::{Context, Poll},
+    FutureExt,
+};
+
+use std::{
+    pin::Pin,
+    sync::{Arc, Mutex, Weak},
+};
+
+#[derive(Default)]
+struct State {
+    // Keeps intermediate tokens alive, and thus connected, for as long as they have descendants.
+    _parent: Option<Arc<Mutex<State>>>,
+    cancelled: bool,
+    children: Vec<Weak<Mutex<State>>>,
+    signals: Vec<oneshot::Sender<()>>,
+}
+
+/// A token that can be cancelled, along with all the tokens derived from it.
+#[derive(Clone, Default)]
+pub struct CancellationToken {
+    state: Arc<Mutex<State>>,
+}
+
+impl CancellationToken {
+    /// Create a new, root, cancellation token.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a child token that is cancelled when this token is cancelled, but that can also be cancelled on its own.
+    ///
+    /// The child of a cancelled token is created cancelled.
+    pub fn child(&self) -> Self {
+        let child = Self {
+            state: Arc::new(Mutex::new(State {
+                _parent: Some(self.state.clone()),
+                ..Default::default()
+            })),
+        };
+        let mut state = self.state.lock().unwrap();
+
+        if state.cancelled {
+            drop(state);
+            child.cancel();
+        } else {
+            state.children.retain(|child| child.strong_count() > 0);
+            state.children.push(Arc::downgrade(&child.state));
+        }
+
+        child
+    }
+
+    /// Cancel this token and all of its descendants.
+    pub fn cancel(&self) {
+        cancel(&self.state);
+    }
+
+    /// Whether this token has been cancelled.
+    pub fn is_cancelled(&self) -> bool {
+        self.state.lock().unwrap().cancelled
+    }
+
+    /// Get a shutdown signal that is triggered when this token is cancelled, e.g. to build a
+    /// [`ShutdownStream`](crate::shutdown_stream::ShutdownStream).
+    ///
+    /// Like any `oneshot` channel, the signal is also triggered if this token, its clones and its descendants are all
+    /// dropped.
+    pub fn signal(&self) -> oneshot::Receiver<()> {
+        let (sender, receiver) = oneshot::channel();
+        let mut state = self.state.lock().unwrap();
+
+        if state.cancelled {
+            // The receiver is still alive, sending can't fail.
+            let _ = sender.send(());
+        } else {
+            state.signals.retain(|signal| !signal.is_canceled());
+            state.signals.push(sender);
+        }
+
+        receiver
+    }
+
+    /// Get a future that resolves when this token is cancelled.
+    pub fn cancelled(&self) -> Cancelled {
+        Cancelled(self.signal())
+    }
+}
+
+fn cancel(state: &Mutex<State>) {
+    let children = {
+        let mut state = state.lock().unwrap();
+
+        if state.cancelled {
+            return;
+        }
+        state.cancelled = true;
+        for signal in state.signals.drain(..) {
+            // The receiver may have been dropped, in which case nobody is waiting for the signal.
+            let _ = signal.send(());
+        }
+
+        std::mem::take(&mut state.children)
+    };
+
+    for child in children.iter().filter_map(Weak::upgrade) {
+        cancel(&child);
+    }
+}
+
+/// A future that resolves when a [`CancellationToken`] is cancelled.
+pub struct Cancelled(oneshot::Receiver<()>);
+
+impl Future for Cancelled {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<Self::Output> {
+        self.0.poll_unpin(cx).map(drop)
+    }
+}
+
+impl FusedFuture for Cancelled {
+    fn is_terminated(&self) -> bool {
+        self.0.is_terminated()
+    }
+}
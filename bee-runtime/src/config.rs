@@ -0,0 +1,256 @@
+// Copyright 2020-2021 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+//! A module that loads layered node configurations.
+//!
+//! A configuration is merged from layers, each layer overriding the keys of the previous ones:
+//! ```ignore
+//! let config = ConfigLoader::new()
+//!     .file("config.toml")
+//!     .env("BEE")
+//!     .set("node.stop_deadline_ms", 5000)
+//!     .load()?;
+//!
+//! let node = BeeNodeBuilder::<Backend>::new(config.node()?)?
+//!     .with_worker_cfg::<Gossip>(config.worker::<_, Gossip>()?)
+//!     .finish()
+//!     .await?;
+//! ```
+//! Each worker reads its own section, `[workers.<section>]`, through the [`ConfiguredWorker`] trait.
+
+use crate::{
+    node::{BeeNodeConfig, BeeNodeConfigBuilder, Node},
+    worker::Worker,
+};
+
+use serde::de::DeserializeOwned;
+use thiserror::Error;
+
+use std::{
+    env,
+    path::{Path, PathBuf},
+};
+
+pub use toml::Value;
+
+/// Errors that may occur while loading a configuration or deserializing its sections.
+#[derive(Error, Debug)]
+#[non_exhaustive]
+pub enum Error {
+    /// A configuration file could not be read.
+    #[error("Reading configuration file `{0}` failed: {1}.")]
+    Io(PathBuf, std::io::Error),
+    /// A configuration file is not valid TOML.
+    #[error("Parsing configuration `{0}` failed: {1}.")]
+    Parse(String, toml::de::Error),
+    /// A key overrides a value that is not a table with a table.
+    #[error("Invalid configuration key `{0}`.")]
+    InvalidKey(String),
+    /// A configuration value could not be deserialized.
+    #[error("Invalid configuration value at `{path}`: {message}.")]
+    InvalidValue {
+        /// The full path of the key that failed, e.g. `workers.gossip.port`.
+        path: String,
+        /// The reason of the failure.
+        message: String,
+    },
+}
+
+enum Layer {
+    File(PathBuf),
+    Toml(String),
+    Env(String, Vec<(String, String)>),
+    Set(String, Value),
+}
+
+/// A loader that merges configuration layers into a [`Config`].
+#[derive(Default)]
+pub struct ConfigLoader {
+    layers: Vec<Layer>,
+}
+
+impl ConfigLoader {
+    /// Creates a new loader without any layer.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a layer read from a TOML file.
+    pub fn file<P: AsRef<Path>>(mut self, path: P) -> Self {
+        self.layers.push(Layer::File(path.as_ref().to_owned()));
+        self
+    }
+
+    /// Adds a layer parsed from a TOML string.
+    pub fn toml<S: Into<String>>(mut self, toml: S) -> Self {
+        self.layers.push(Layer::Toml(toml.into()));
+        self
+    }
+
+    /// Adds a layer made of the environment variables starting with `<prefix>_`.
+    ///
+    /// Variable names are lowercased and nested tables are separated by double underscores, e.g.
+    /// `BEE_WORKERS__GOSSIP__PORT` sets the `workers.gossip.port` key. Values are parsed as TOML values when possible,
+    /// and used as strings otherwise.
+    pub fn env(self, prefix: &str) -> Self {
+        self.env_vars(prefix, env::vars())
+    }
+
+    /// Adds a layer made of the given variables starting with `<prefix>_`, like [`ConfigLoader::env`].
+    pub fn env_vars<I: IntoIterator<Item = (String, String)>>(mut self, prefix: &str, vars: I) -> Self {
+        self.layers
+            .push(Layer::Env(prefix.to_owned(), vars.into_iter().collect()));
+        self
+    }
+
+    /// Adds a layer that sets a single key, given as a dot-separated path.
+    pub fn set<K: Into<String>, V: Into<Value>>(mut self, key: K, value: V) -> Self {
+        self.layers.push(Layer::Set(key.into(), value.into()));
+        self
+    }
+
+    /// Merges all the layers, in the order they were added.
+    pub fn load(self) -> Result<Config, Error> {
+        let mut root = Value::Table(Default::default());
+
+        for layer in self.layers {
+            match layer {
+                Layer::File(path) => {
+                    let toml = std::fs::read_to_string(&path).map_err(|e| Error::Io(path.clone(), e))?;
+                    merge(&mut root, parse(&path.display().to_string(), &toml)?);
+                }
+                Layer::Toml(toml) => merge(&mut root, parse("<string>", &toml)?),
+                Layer::Env(prefix, vars) => {
+                    let prefix = format!("{}_", prefix);
+
+                    for (name, raw) in vars {
+                        if let Some(name) = name.strip_prefix(&prefix) {
+                            let key = name.to_lowercase().replace("__", ".");
+                            set(&mut root, &key, parse_value(&raw))?;
+                        }
+                    }
+                }
+                Layer::Set(key, value) => set(&mut root, &key, value)?,
+            }
+        }
+
+        Ok(Config { root })
+    }
+}
+
+/// A worker whose configuration can be read from its own section of a [`Config`].
+pub trait ConfiguredWorker<N: Node>: Worker<N> {
+    /// The name of the section, under `workers`, that holds the configuration of this worker.
+    const SECTION: &'static str;
+
+    /// Helps build the associated `Config`.
+    type ConfigBuilder: Default + DeserializeOwned + Into<Self::Config>;
+}
+
+/// A merged node configuration.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Config {
+    root: Value,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            root: Value::Table(Default::default()),
+        }
+    }
+}
+
+impl Config {
+    /// Gets the value at the given dot-separated key path, if any.
+    pub fn get(&self, key: &str) -> Option<&Value> {
+        key.split('.')
+            .filter(|segment| !segment.is_empty())
+            .try_fold(&self.root, |value, segment| value.get(segment))
+    }
+
+    /// Deserializes the section at the given key path with the builder `B`, using the default builder if the section
+    /// is missing, and builds the configuration `C` out of it.
+    pub fn section<B, C>(&self, key: &str) -> Result<C, Error>
+    where
+        B: Default + DeserializeOwned + Into<C>,
+    {
+        let value = match self.get(key) {
+            Some(value) => value.clone(),
+            None => return Ok(B::default().into()),
+        };
+
+        serde_path_to_error::deserialize::<_, B>(value)
+            .map(Into::into)
+            .map_err(|e| {
+                let path = e.path().to_string();
+                Error::InvalidValue {
+                    path: if path == "." {
+                        key.to_owned()
+                    } else {
+                        format!("{}.{}", key, path)
+                    },
+                    message: e.into_inner().to_string(),
+                }
+            })
+    }
+
+    /// Builds the configuration of a [`BeeNode`](crate::node::BeeNode) out of the `node` section.
+    pub fn node(&self) -> Result<BeeNodeConfig, Error> {
+        self.section::<BeeNodeConfigBuilder, _>("node")
+    }
+
+    /// Builds the configuration of the worker `W` out of its `workers.<section>` section.
+    pub fn worker<N: Node, W: ConfiguredWorker<N>>(&self) -> Result<W::Config, Error> {
+        self.section::<W::ConfigBuilder, _>(&format!("workers.{}", W::SECTION))
+    }
+}
+
+fn parse(source: &str, toml: &str) -> Result<Value, Error> {
+    toml.parse::<Value>().map_err(|e| Error::Parse(source.to_owned(), e))
+}
+
+fn parse_value(raw: &str) -> Value {
+    format!("value = {}", raw)
+        .parse::<Value>()
+        .ok()
+        .and_then(|mut table| table.as_table_mut().and_then(|table| table.remove("value")))
+        .unwrap_or_else(|| Value::String(raw.to_owned()))
+}
+
+fn merge(into: &mut Value, from: Value) {
+    match (into, from) {
+        (Value::Table(into), Value::Table(from)) => {
+            for (key, value) in from {
+                match into.get_mut(&key) {
+                    Some(existing) => merge(existing, value),
+                    None => {
+                        into.insert(key, value);
+                    }
+                }
+            }
+        }
+        (into, from) => *into = from,
+    }
+}
+
+fn set(root: &mut Value, key: &str, value: Value) -> Result<(), Error> {
+    let mut segments = key.split('.').collect::<Vec<_>>();
+    let last = match segments.pop() {
+        Some(last) if !last.is_empty() => last,
+        _ => return Err(Error::InvalidKey(key.to_owned())),
+    };
+    let mut table = root.as_table_mut().ok_or_else(|| Error::InvalidKey(key.to_owned()))?;
+
+    for segment in segments {
+        table = table
+            .entry(segment.to_owned())
+            .or_insert_with(|| Value::Table(Default::default()))
+            .as_table_mut()
+            .ok_or_else(|| Error::InvalidKey(key.to_owned()))?;
+    }
+
+    table.insert(last.to_owned(), value);
+
+    Ok(())
+}
@@ -0,0 +1,152 @@
+// Copyright 2020-2021 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::shutdown_stream::ShutdownStream;
+
+use dashmap::DashMap;
+use futures::{
+    channel::{mpsc, oneshot},
+    lock::Mutex,
+    stream::{self, FusedStream},
+    task::{Context, Poll},
+    SinkExt, Stream, StreamExt,
+};
+
+use std::{
+    any::{Any, TypeId},
+    pin::Pin,
+    sync::Arc,
+};
+
+/// The behaviour of an [`AsyncBus`] when the channel of a subscriber is full.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Backpressure {
+    /// The event is dropped for this subscriber, the dispatcher doesn't wait.
+    Drop,
+    /// The dispatcher waits until the subscriber has room for the event.
+    Block,
+}
+
+struct Subscriber<E> {
+    // Sharing a single sender, instead of cloning it, keeps the channel bounded as every sender owns a slot.
+    sender: Arc<Mutex<mpsc::Sender<E>>>,
+    backpressure: Backpressure,
+}
+
+impl<E> Subscriber<E> {
+    fn is_closed(&self) -> bool {
+        // A locked sender is being used by a dispatcher and will be checked again on the next dispatch.
+        matches!(self.sender.try_lock(), Some(sender) if sender.is_closed())
+    }
+}
+
+impl<E> Clone for Subscriber<E> {
+    fn clone(&self) -> Self {
+        Self {
+            sender: self.sender.clone(),
+            backpressure: self.backpressure,
+        }
+    }
+}
+
+/// An asynchronous event bus for arbitrary event types.
+///
+/// Unlike [`Bus`](crate::event::Bus), events are not handled by callbacks but delivered to subscribers through bounded
+/// channels. No lock is held while events are being delivered, so slow subscribers never block other event types and
+/// subscribers may dispatch events themselves.
+#[derive(Default)]
+pub struct AsyncBus {
+    subscribers: DashMap<TypeId, Box<dyn Any + Send + Sync>>,
+}
+
+impl AsyncBus {
+    /// Subscribe to events of type `E`, buffering up to `capacity` of them in addition to the one being sent.
+    ///
+    /// The `backpressure` argument determines what happens when the subscriber falls behind.
+    pub fn subscribe<E: Clone + Send + Sync + 'static>(
+        &self,
+        capacity: usize,
+        backpressure: Backpressure,
+    ) -> Subscription<E> {
+        let (sender, receiver) = mpsc::channel(capacity);
+
+        self.subscribers
+            .entry(TypeId::of::<E>())
+            .or_insert_with(|| Box::new(Vec::<Subscriber<E>>::new()))
+            .downcast_mut::<Vec<Subscriber<E>>>()
+            .expect("Invalid subscribers")
+            .push(Subscriber {
+                sender: Arc::new(Mutex::new(sender)),
+                backpressure,
+            });
+
+        Subscription(receiver)
+    }
+
+    /// Subscribe to events of type `E`, like [`AsyncBus::subscribe`], with a subscription that ends when the given
+    /// shutdown signal, e.g. the one of a worker task, is triggered.
+    pub fn subscribe_until<E: Clone + Send + Sync + 'static>(
+        &self,
+        shutdown: oneshot::Receiver<()>,
+        capacity: usize,
+        backpressure: Backpressure,
+    ) -> ShutdownStream<stream::Fuse<Subscription<E>>> {
+        ShutdownStream::new(shutdown, self.subscribe(capacity, backpressure))
+    }
+
+    /// Dispatch an event to all active subscribers of its type.
+    ///
+    /// Subscribers that have been dropped are removed in the process.
+    pub async fn dispatch<E: Clone + Send + Sync + 'static>(&self, event: E) {
+        let subscribers = {
+            let mut entry = match self.subscribers.get_mut(&TypeId::of::<E>()) {
+                Some(entry) => entry,
+                None => return,
+            };
+            let subscribers = entry.downcast_mut::<Vec<Subscriber<E>>>().expect("Invalid subscribers");
+
+            subscribers.retain(|subscriber| !subscriber.is_closed());
+            subscribers.clone()
+        };
+
+        for subscriber in subscribers {
+            let mut sender = subscriber.sender.lock().await;
+            // Sending only fails if the subscriber is gone or, when dropping, full; either way, there is nothing to do.
+            let _ = match subscriber.backpressure {
+                Backpressure::Drop => sender.try_send(event.clone()).map_err(|e| e.into_send_error()),
+                Backpressure::Block => sender.send(event.clone()).await,
+            };
+        }
+    }
+
+    /// Get the number of active subscribers for events of type `E`.
+    pub fn subscriber_count<E: Any>(&self) -> usize {
+        self.subscribers
+            .get(&TypeId::of::<E>())
+            .and_then(|entry| {
+                entry
+                    .downcast_ref::<Vec<Subscriber<E>>>()
+                    .map(|subscribers| subscribers.iter().filter(|s| !s.is_closed()).count())
+            })
+            .unwrap_or(0)
+    }
+}
+
+/// A stream of events of type `E` dispatched through an [`AsyncBus`].
+///
+/// Dropping the subscription unsubscribes from the bus.
+pub struct Subscription<E>(mpsc::Receiver<E>);
+
+impl<E> Stream for Subscription<E> {
+    type Item = E;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<Option<Self::Item>> {
+        self.0.poll_next_unpin(cx)
+    }
+}
+
+impl<E> FusedStream for Subscription<E> {
+    fn is_terminated(&self) -> bool {
+        self.0.is_terminated()
+    }
+}
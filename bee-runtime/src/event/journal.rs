@@ -0,0 +1,230 @@
+// Copyright 2020-2021 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::event::{Bus, ListenerHandle, Priority};
+
+use bee_common::packable::{Packable, Read, Write};
+
+use log::error;
+use thiserror::Error;
+
+use std::{
+    any::{type_name, Any},
+    collections::HashMap,
+    convert::TryFrom,
+    sync::{Arc, Mutex},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+/// Errors that may occur while reading or replaying an event journal.
+#[derive(Error, Debug)]
+#[non_exhaustive]
+pub enum JournalError {
+    /// Reading from or writing to the journal failed.
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+    /// The type name of a journal entry is not valid UTF-8.
+    #[error("Invalid type name in journal entry.")]
+    InvalidTypeName,
+    /// The journal contains an event type that was not registered with the replayer.
+    #[error("Event type `{0}` was not registered with the replayer.")]
+    UnknownEvent(String),
+    /// The payload of a journal entry could not be unpacked into its event type.
+    #[error("Unpacking event `{0}` failed: {1}.")]
+    InvalidEvent(String, String),
+    /// A field of a journal entry is too long to be packed with its length prefix.
+    #[error("The {0} of a journal entry is too long: {1} bytes.")]
+    TooLong(&'static str, usize),
+}
+
+/// A single event recorded in an event journal.
+///
+/// Entries are packed as the little-endian millisecond timestamp (`u64`), the length of the type name (`u16`) followed
+/// by the type name itself, and the length of the payload (`u32`) followed by the packed event itself.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct JournalEntry {
+    /// The time at which the event was dispatched, in milliseconds since the Unix epoch.
+    pub timestamp: u64,
+    /// The name of the event type.
+    pub type_name: String,
+    /// The packed event.
+    pub payload: Vec<u8>,
+}
+
+impl JournalEntry {
+    /// Read the next entry of a journal, returning `None` if the end of the journal has been reached.
+    pub fn read<R: Read + ?Sized>(reader: &mut R) -> Result<Option<Self>, JournalError> {
+        let mut first = [0u8; 1];
+
+        if reader.read(&mut first)? == 0 {
+            return Ok(None);
+        }
+
+        Self::unpack(&mut first.as_ref().chain(reader)).map(Some)
+    }
+}
+
+impl Packable for JournalEntry {
+    type Error = JournalError;
+
+    fn packed_len(&self) -> usize {
+        self.timestamp.packed_len() + 0u16.packed_len() + self.type_name.len() + 0u32.packed_len() + self.payload.len()
+    }
+
+    fn pack<W: Write>(&self, writer: &mut W) -> Result<(), Self::Error> {
+        // Both lengths are checked before anything is written, not to leave a partial entry in the journal.
+        let type_name_len = u16::try_from(self.type_name.len())
+            .map_err(|_| JournalError::TooLong("type name", self.type_name.len()))?;
+        let payload_len =
+            u32::try_from(self.payload.len()).map_err(|_| JournalError::TooLong("payload", self.payload.len()))?;
+
+        self.timestamp.pack(writer)?;
+        type_name_len.pack(writer)?;
+        writer.write_all(self.type_name.as_bytes())?;
+        payload_len.pack(writer)?;
+        writer.write_all(&self.payload)?;
+
+        Ok(())
+    }
+
+    fn unpack<R: Read + ?Sized>(reader: &mut R) -> Result<Self, Self::Error>
+    where
+        Self: Sized,
+    {
+        let timestamp = u64::unpack(reader)?;
+
+        let type_name_len = u16::unpack(reader)?;
+        let type_name = read_bytes(reader, type_name_len as u64)?;
+        let type_name = String::from_utf8(type_name).map_err(|_| JournalError::InvalidTypeName)?;
+
+        let payload_len = u32::unpack(reader)?;
+        let payload = read_bytes(reader, payload_len as u64)?;
+
+        Ok(Self {
+            timestamp,
+            type_name,
+            payload,
+        })
+    }
+}
+
+/// Read exactly `len` bytes, growing the buffer with the bytes actually read rather than trusting `len` up front.
+fn read_bytes<R: Read + ?Sized>(reader: &mut R, len: u64) -> Result<Vec<u8>, JournalError> {
+    let mut bytes = Vec::new();
+
+    reader.take(len).read_to_end(&mut bytes)?;
+
+    if (bytes.len() as u64) < len {
+        return Err(std::io::Error::from(std::io::ErrorKind::UnexpectedEof).into());
+    }
+
+    Ok(bytes)
+}
+
+/// A recording layer that writes events dispatched through a [`Bus`] to a journal.
+///
+/// Recording is opt-in: only the event types passed to [`Recorder::record`] are written to the journal.
+pub struct Recorder<W> {
+    writer: Arc<Mutex<W>>,
+}
+
+impl<W: Write + Send + 'static> Recorder<W> {
+    /// Create a new recorder that writes its journal to the given writer.
+    pub fn new(writer: W) -> Self {
+        Self {
+            writer: Arc::new(Mutex::new(writer)),
+        }
+    }
+
+    /// Start recording events of type `E` dispatched through the given bus, until the returned handle is dropped.
+    ///
+    /// Events are recorded before any other listener of the bus gets to handle them.
+    pub fn record<'a, E, ID>(&self, bus: &Bus<'a, ID>, id: ID) -> ListenerHandle<'a, ID>
+    where
+        E: Packable + Any,
+        ID: Clone + PartialEq,
+    {
+        let writer = self.writer.clone();
+
+        bus.add_listener_raw_with_priority(id, Priority(i32::MAX), move |event: &E| {
+            let entry = JournalEntry {
+                timestamp: SystemTime::now()
+                    .duration_since(UNIX_EPOCH)
+                    .map(|d| d.as_millis() as u64)
+                    .unwrap_or_default(),
+                type_name: type_name::<E>().to_owned(),
+                payload: event.pack_new(),
+            };
+
+            if let Err(e) = entry.pack(&mut *writer.lock().unwrap()) {
+                error!("Recording event `{}` failed: {}.", type_name::<E>(), e);
+            }
+        })
+    }
+
+    /// Flush the underlying writer.
+    pub fn flush(&self) -> Result<(), JournalError> {
+        Ok(self.writer.lock().unwrap().flush()?)
+    }
+
+    /// Get back the underlying writer, returning `None` if events are still being recorded.
+    pub fn into_inner(self) -> Option<W> {
+        Arc::try_unwrap(self.writer)
+            .ok()
+            .map(|writer| writer.into_inner().unwrap())
+    }
+}
+
+type Decoder<ID> = fn(&[u8], &Bus<'_, ID>) -> Result<(), JournalError>;
+
+/// A replayer that reads a journal written by a [`Recorder`] and re-dispatches its events, in order, into a [`Bus`].
+pub struct Replayer<ID = std::any::TypeId> {
+    decoders: HashMap<&'static str, Decoder<ID>>,
+}
+
+impl<ID> Default for Replayer<ID> {
+    fn default() -> Self {
+        Self {
+            decoders: HashMap::new(),
+        }
+    }
+}
+
+impl<ID: Clone + PartialEq> Replayer<ID> {
+    /// Create a new replayer that doesn't know about any event type yet.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Allow events of type `E` to be replayed.
+    pub fn with_event<E: Packable + Any>(mut self) -> Self {
+        self.decoders.insert(type_name::<E>(), decode::<E, ID>);
+        self
+    }
+
+    /// Dispatch all the events of a journal into the given bus, returning the number of replayed events.
+    pub fn replay<R: Read + ?Sized>(&self, reader: &mut R, bus: &Bus<'_, ID>) -> Result<usize, JournalError> {
+        let mut count = 0;
+
+        while let Some(entry) = JournalEntry::read(reader)? {
+            let decode = self
+                .decoders
+                .get(entry.type_name.as_str())
+                .ok_or(JournalError::UnknownEvent(entry.type_name.clone()))?;
+
+            decode(&entry.payload, bus)?;
+            count += 1;
+        }
+
+        Ok(count)
+    }
+}
+
+fn decode<E: Packable + Any, ID: Clone + PartialEq>(mut payload: &[u8], bus: &Bus<'_, ID>) -> Result<(), JournalError> {
+    let event = E::unpack(&mut payload)
+        .map_err(|e| JournalError::InvalidEvent(type_name::<E>().to_owned(), format!("{:?}", e)))?;
+
+    bus.dispatch(event);
+
+    Ok(())
+}
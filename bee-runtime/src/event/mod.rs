@@ -0,0 +1,233 @@
+// Copyright 2020-2021 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+//! A module that provides a generic, type-safe event bus for arbitrary event types.
+
+mod async_bus;
+mod journal;
+
+pub use async_bus::{AsyncBus, Backpressure, Subscription};
+pub use journal::{JournalEntry, JournalError, Recorder, Replayer};
+
+use crate::metrics::{DispatchCounters, MetricsRegistry};
+
+use dashmap::DashMap;
+
+use std::{
+    any::{Any, TypeId},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex, Weak,
+    },
+};
+
+type Listener<'a> = dyn Fn(&dyn Any) + Send + Sync + 'a;
+type Listeners<'a, ID> = DashMap<TypeId, Vec<Entry<'a, ID>>>;
+
+struct Entry<'a, ID> {
+    key: usize,
+    priority: Priority,
+    once: bool,
+    listener: Box<Listener<'a>>,
+    id: ID,
+}
+
+/// The priority of an event listener.
+///
+/// Listeners of an event type with a higher priority are invoked before those with a lower one, and listeners with the
+/// same priority are invoked in the order they were added.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Priority(pub i32);
+
+/// A handle to a single event listener, that removes the listener from its event bus when dropped.
+#[must_use = "dropping the handle removes the listener"]
+pub struct ListenerHandle<'a, ID = TypeId> {
+    listeners: Weak<Listeners<'a, ID>>,
+    event: TypeId,
+    key: usize,
+}
+
+impl<'a, ID> ListenerHandle<'a, ID> {
+    /// Consume the handle without removing the listener, which then lives as long as its ID is not removed.
+    pub fn detach(mut self) {
+        self.listeners = Weak::new();
+    }
+}
+
+impl<'a, ID> Drop for ListenerHandle<'a, ID> {
+    fn drop(&mut self) {
+        if let Some(listeners) = self.listeners.upgrade() {
+            if let Some(mut ls) = listeners.get_mut(&self.event) {
+                ls.retain(|entry| entry.key != self.key);
+            }
+        }
+    }
+}
+
+/// An event bus for arbitrary event types.
+pub struct Bus<'a, ID = TypeId> {
+    listeners: Arc<Listeners<'a, ID>>,
+    next_key: AtomicUsize,
+    dispatches: Option<DispatchCounters>,
+}
+
+impl<'a, ID> Default for Bus<'a, ID> {
+    fn default() -> Self {
+        Self {
+            listeners: Arc::default(),
+            next_key: AtomicUsize::new(0),
+            dispatches: None,
+        }
+    }
+}
+
+impl<'a, ID> Bus<'a, ID> {
+    /// Create an event bus that counts the dispatched events, by event type, in the `bee_bus_dispatches_total` counter
+    /// of the given registry.
+    pub fn with_metrics(metrics: MetricsRegistry) -> Self {
+        Self {
+            dispatches: Some(DispatchCounters::new(metrics)),
+            ..Self::default()
+        }
+    }
+}
+
+impl<'a, ID: Clone + PartialEq> Bus<'a, ID> {
+    /// Dispatch an event via this event bus.
+    ///
+    /// All active listeners registered for this event will be invoked, by decreasing priority. One-shot listeners are
+    /// removed afterwards.
+    pub fn dispatch<E: Any>(&self, event: E) {
+        if let Some(dispatches) = &self.dispatches {
+            dispatches.inc::<E>();
+        }
+
+        if let Some(mut ls) = self.listeners.get_mut(&TypeId::of::<E>()) {
+            ls.iter().for_each(|entry| (entry.listener)(&event));
+            ls.retain(|entry| !entry.once);
+        }
+    }
+
+    /// Add an event listener bound to a specific event type, `E`, and registered with the given ID.
+    ///
+    /// The listener is removed when the returned handle is dropped, unless it is detached.
+    pub fn add_listener_raw<E: Any, F: Fn(&E) + Send + Sync + 'a>(&self, id: ID, handler: F) -> ListenerHandle<'a, ID> {
+        self.add_listener_raw_with_priority(id, Priority::default(), handler)
+    }
+
+    /// Add an event listener bound to a specific event type, `E`, and registered with the given ID and priority.
+    ///
+    /// The listener is removed when the returned handle is dropped, unless it is detached.
+    pub fn add_listener_raw_with_priority<E: Any, F: Fn(&E) + Send + Sync + 'a>(
+        &self,
+        id: ID,
+        priority: Priority,
+        handler: F,
+    ) -> ListenerHandle<'a, ID> {
+        self.insert::<E>(
+            id,
+            priority,
+            false,
+            Box::new(move |event| handler(&event.downcast_ref().expect("Invalid event"))),
+        )
+    }
+
+    /// Add an event listener bound to a specific event type, `E`, and registered with the given ID, that is removed
+    /// after handling its first event.
+    ///
+    /// The listener is removed when the returned handle is dropped, unless it is detached.
+    pub fn add_once_listener_raw<E: Any, F: FnOnce(&E) + Send + 'a>(
+        &self,
+        id: ID,
+        handler: F,
+    ) -> ListenerHandle<'a, ID> {
+        let handler = Mutex::new(Some(handler));
+
+        self.insert::<E>(
+            id,
+            Priority::default(),
+            true,
+            Box::new(move |event| {
+                if let Some(handler) = handler.lock().unwrap().take() {
+                    handler(event.downcast_ref().expect("Invalid event"))
+                }
+            }),
+        )
+    }
+
+    /// Remove all event listeners registered with the given ID, dropping them in the process.
+    pub fn remove_listeners_by_id(&self, id: ID) {
+        self.listeners
+            .iter_mut()
+            .for_each(|mut listeners| listeners.retain(|entry| entry.id != id));
+    }
+
+    fn insert<E: Any>(
+        &self,
+        id: ID,
+        priority: Priority,
+        once: bool,
+        listener: Box<Listener<'a>>,
+    ) -> ListenerHandle<'a, ID> {
+        let key = self.next_key.fetch_add(1, Ordering::Relaxed);
+        let mut ls = self.listeners.entry(TypeId::of::<E>()).or_default();
+        let index = ls
+            .iter()
+            .position(|entry| entry.priority < priority)
+            .unwrap_or_else(|| ls.len());
+
+        ls.insert(
+            index,
+            Entry {
+                key,
+                priority,
+                once,
+                listener,
+                id,
+            },
+        );
+
+        ListenerHandle {
+            listeners: Arc::downgrade(&self.listeners),
+            event: TypeId::of::<E>(),
+            key,
+        }
+    }
+}
+
+impl<'a> Bus<'a, TypeId> {
+    /// Add an event listener bound to a specific event type, `E`, and bound to a type `T`.
+    ///
+    /// This event listener will be removed when [`Bus::remove_listeners_by_id`] is called with the `TypeId` of `T`.
+    pub fn add_listener<T: Any, E: Any, F: Fn(&E) + Send + Sync + 'a>(&self, handler: F) {
+        self.add_listener_raw(TypeId::of::<T>(), handler).detach();
+    }
+
+    /// Add an event listener bound to a specific event type, `E`, and bound to a type `T`, with the given priority.
+    ///
+    /// This event listener will be removed when [`Bus::remove_listeners_by_id`] is called with the `TypeId` of `T`.
+    pub fn add_listener_with_priority<T: Any, E: Any, F: Fn(&E) + Send + Sync + 'a>(
+        &self,
+        priority: Priority,
+        handler: F,
+    ) {
+        self.add_listener_raw_with_priority(TypeId::of::<T>(), priority, handler)
+            .detach();
+    }
+
+    /// Add an event listener bound to a specific event type, `E`, and bound to a type `T`, that is removed after
+    /// handling its first event.
+    ///
+    /// This event listener will also be removed when [`Bus::remove_listeners_by_id`] is called with the `TypeId` of
+    /// `T`.
+    pub fn add_once_listener<T: Any, E: Any, F: FnOnce(&E) + Send + 'a>(&self, handler: F) {
+        self.add_once_listener_raw(TypeId::of::<T>(), handler).detach();
+    }
+
+    /// Add an event listener bound to a specific event type, `E`, registered using a hidden type that will prevent its
+    /// removal until the event bus is dropped.
+    pub fn add_static_listener<E: Any, F: Fn(&E) + Send + Sync + 'a>(&self, handler: F) {
+        struct Static;
+        self.add_listener_raw(TypeId::of::<Static>(), handler).detach();
+    }
+}
@@ -0,0 +1,253 @@
+// Copyright 2020-2021 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::executor::Executor;
+
+use futures::{
+    executor::LocalPool,
+    future::{BoxFuture, Future},
+    task::{self, ArcWake, Context, Poll, SpawnExt, Waker},
+};
+
+use std::{
+    collections::BTreeMap,
+    pin::Pin,
+    sync::{Arc, Mutex},
+    thread::{self, Thread},
+    time::Instant,
+};
+
+// Timers are keyed by their deadline, then by a unique id, such that a `Sleep` can update or remove its own timer.
+type TimerKey = (Instant, u64);
+
+struct Timers {
+    next_id: u64,
+    wakers: BTreeMap<TimerKey, Waker>,
+}
+
+struct Shared {
+    thread: Thread,
+    spawned: Mutex<Vec<BoxFuture<'static, ()>>>,
+    timers: Mutex<Timers>,
+}
+
+impl Shared {
+    // Wake the expired timers, returning whether there were any, and the deadline of the next one.
+    fn fire_timers(&self) -> (bool, Option<Instant>) {
+        let now = Instant::now();
+        let mut timers = self.timers.lock().unwrap();
+        let mut fired = false;
+
+        while let Some(entry) = timers.wakers.first_entry() {
+            if entry.key().0 > now {
+                return (fired, Some(entry.key().0));
+            }
+            entry.remove().wake();
+            fired = true;
+        }
+
+        (fired, None)
+    }
+}
+
+// Unparks the thread of the runtime, on top of waking the task, such that the runtime never misses a wake-up while
+// waiting for the next timer.
+struct Unpark {
+    waker: Option<Waker>,
+    thread: Thread,
+}
+
+impl ArcWake for Unpark {
+    fn wake_by_ref(arc_self: &Arc<Self>) {
+        if let Some(waker) = &arc_self.waker {
+            waker.wake_by_ref();
+        }
+        arc_self.thread.unpark();
+    }
+}
+
+struct Task {
+    future: BoxFuture<'static, ()>,
+    thread: Thread,
+    waker: Option<(Waker, Waker)>,
+}
+
+impl Future for Task {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        let this = self.get_mut();
+        let waker = match &this.waker {
+            Some((inner, waker)) if inner.will_wake(cx.waker()) => waker.clone(),
+            _ => {
+                let waker = task::waker(Arc::new(Unpark {
+                    waker: Some(cx.waker().clone()),
+                    thread: this.thread.clone(),
+                }));
+                this.waker = Some((cx.waker().clone(), waker.clone()));
+                waker
+            }
+        };
+
+        this.future.as_mut().poll(&mut Context::from_waker(&waker))
+    }
+}
+
+/// A single-threaded runtime, built on a [`LocalPool`], that runs the tasks spawned through its [`LocalExecutor`]s.
+///
+/// Tasks only make progress while [`LocalRuntime::block_on`] is running, on the thread that created the runtime, and
+/// blocking work runs on that same thread.
+pub struct LocalRuntime {
+    pool: LocalPool,
+    shared: Arc<Shared>,
+}
+
+impl Default for LocalRuntime {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LocalRuntime {
+    /// Create a runtime that runs on the current thread.
+    pub fn new() -> Self {
+        Self {
+            pool: LocalPool::new(),
+            shared: Arc::new(Shared {
+                thread: thread::current(),
+                spawned: Mutex::new(Vec::new()),
+                timers: Mutex::new(Timers {
+                    next_id: 0,
+                    wakers: BTreeMap::new(),
+                }),
+            }),
+        }
+    }
+
+    /// Get an executor that spawns its tasks on this runtime.
+    pub fn executor(&self) -> LocalExecutor {
+        LocalExecutor {
+            shared: self.shared.clone(),
+        }
+    }
+
+    /// Run `future`, along with the spawned tasks, until it completes.
+    ///
+    /// Spawned tasks that are still pending when `future` completes are resumed by the next call.
+    pub fn block_on<F: Future>(&mut self, future: F) -> F::Output {
+        let mut future = Box::pin(future);
+        let waker = task::waker(Arc::new(Unpark {
+            waker: None,
+            thread: self.shared.thread.clone(),
+        }));
+        let mut cx = Context::from_waker(&waker);
+        let spawner = self.pool.spawner();
+
+        loop {
+            if let Poll::Ready(output) = future.as_mut().poll(&mut cx) {
+                return output;
+            }
+
+            loop {
+                let spawned = std::mem::take(&mut *self.shared.spawned.lock().unwrap());
+
+                for future in spawned {
+                    let task = Task {
+                        future,
+                        thread: self.shared.thread.clone(),
+                        waker: None,
+                    };
+                    spawner.spawn(task).expect("the pool outlives its spawner");
+                }
+                self.pool.run_until_stalled();
+
+                if self.shared.spawned.lock().unwrap().is_empty() {
+                    break;
+                }
+            }
+
+            match self.shared.fire_timers() {
+                (true, _) => continue,
+                (false, Some(deadline)) => thread::park_timeout(deadline.saturating_duration_since(Instant::now())),
+                (false, None) => thread::park(),
+            }
+        }
+    }
+}
+
+/// An executor that spawns its tasks on a [`LocalRuntime`].
+///
+/// Its blocking work runs on the thread of the runtime, blocking all the other tasks in the meantime.
+#[derive(Clone)]
+pub struct LocalExecutor {
+    shared: Arc<Shared>,
+}
+
+impl Executor for LocalExecutor {
+    fn spawn_boxed(&self, future: BoxFuture<'static, ()>) {
+        self.shared.spawned.lock().unwrap().push(future);
+        self.shared.thread.unpark();
+    }
+
+    fn spawn_blocking_boxed(&self, f: Box<dyn FnOnce() + Send>) -> BoxFuture<'static, ()> {
+        Box::pin(async move { f() })
+    }
+
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+
+    fn sleep_until(&self, deadline: Instant) -> BoxFuture<'static, ()> {
+        Box::pin(Sleep {
+            deadline,
+            shared: self.shared.clone(),
+            timer: None,
+        })
+    }
+}
+
+// A sleep registers a single timer, on its first pending poll, and deregisters it when dropped.
+struct Sleep {
+    deadline: Instant,
+    shared: Arc<Shared>,
+    timer: Option<TimerKey>,
+}
+
+impl Future for Sleep {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        let this = self.get_mut();
+
+        if Instant::now() >= this.deadline {
+            return Poll::Ready(());
+        }
+
+        let mut timers = this.shared.timers.lock().unwrap();
+
+        match this.timer.and_then(|key| timers.wakers.get_mut(&key)) {
+            Some(waker) => {
+                if !waker.will_wake(cx.waker()) {
+                    *waker = cx.waker().clone();
+                }
+            }
+            None => {
+                let key = (this.deadline, timers.next_id);
+
+                timers.next_id += 1;
+                timers.wakers.insert(key, cx.waker().clone());
+                this.timer = Some(key);
+            }
+        }
+
+        Poll::Pending
+    }
+}
+
+impl Drop for Sleep {
+    fn drop(&mut self) {
+        if let Some(key) = self.timer.take() {
+            self.shared.timers.lock().unwrap().wakers.remove(&key);
+        }
+    }
+}
@@ -0,0 +1,191 @@
+// Copyright 2020-2021 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+//! A module that abstracts the executor running the tasks of a node.
+//!
+//! Nodes spawn their tasks, wait and run blocking work through an [`Executor`], such that the same workers run
+//! unchanged on a tokio runtime, with the [`TokioExecutor`], or on a single thread, with a [`LocalRuntime`]. Other
+//! runtimes, e.g. async-std, only need to implement the four methods of the trait. Workers reach the executor of their
+//! node through [`Node::executor`](crate::node::Node::executor):
+//! ```ignore
+//! let executor = node.executor();
+//!
+//! node.spawn::<MyWorker, _, _>(|shutdown| async move {
+//!     let digest = executor.spawn_blocking(move || hash(&data)).await;
+//!     executor.sleep(Duration::from_secs(1)).await;
+//! });
+//! ```
+//! Workers doing I/O, like the [`SignalWorker`](crate::signal::SignalWorker) or the
+//! [`MetricsExporter`](crate::metrics::MetricsExporter), still need a tokio runtime.
+
+mod local;
+
+pub use local::{LocalExecutor, LocalRuntime};
+
+use futures::{
+    channel::oneshot,
+    future::{AbortHandle, Abortable, BoxFuture, Future, FutureExt},
+    task::{Context, Poll},
+};
+use thiserror::Error;
+
+use std::{
+    any::Any,
+    panic::{self, AssertUnwindSafe},
+    pin::Pin,
+    time::{Duration, Instant},
+};
+
+/// The error returned by a [`TaskHandle`] when its task didn't run to completion.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum JoinError {
+    /// The task was aborted, or dropped by its executor, before completion.
+    #[error("Task was aborted.")]
+    Aborted,
+    /// The task panicked, with the given message.
+    #[error("Task panicked: {0}")]
+    Panicked(String),
+}
+
+/// An executor able to spawn tasks, run blocking work and wait.
+///
+/// Its methods deal with boxed futures, to be usable as a trait object, and the typed versions are provided by
+/// [`ExecutorExt`].
+pub trait Executor: Send + Sync + 'static {
+    /// Spawn a task that runs in the background until completion.
+    fn spawn_boxed(&self, future: BoxFuture<'static, ()>);
+
+    /// Create a future that runs the blocking work `f` to completion, if possible without preventing other tasks from
+    /// making progress in the meantime.
+    fn spawn_blocking_boxed(&self, f: Box<dyn FnOnce() + Send>) -> BoxFuture<'static, ()>;
+
+    /// Get the current time of the clock of the executor.
+    fn now(&self) -> Instant;
+
+    /// Create a future that completes once the clock of the executor reaches `deadline`.
+    fn sleep_until(&self, deadline: Instant) -> BoxFuture<'static, ()>;
+}
+
+/// Typed helpers built on top of any [`Executor`].
+pub trait ExecutorExt: Executor {
+    /// Spawn a task, returning a handle to its output.
+    ///
+    /// Dropping the handle detaches the task, which keeps running.
+    fn spawn<F>(&self, future: F) -> TaskHandle<F::Output>
+    where
+        F: Future + Send + 'static,
+        F::Output: Send + 'static,
+    {
+        #[cfg(feature = "simulation")]
+        let future = crate::simulation::Perturbed::new(future);
+
+        let (abort, registration) = AbortHandle::new_pair();
+        let (sender, receiver) = oneshot::channel();
+        let task = Abortable::new(AssertUnwindSafe(future).catch_unwind(), registration);
+
+        self.spawn_boxed(Box::pin(async move {
+            let result = match task.await {
+                Ok(Ok(output)) => Ok(output),
+                Ok(Err(payload)) => Err(JoinError::Panicked(panic_message(payload))),
+                Err(_) => Err(JoinError::Aborted),
+            };
+            // The handle may have been dropped to detach the task.
+            let _ = sender.send(result);
+        }));
+
+        TaskHandle { abort, receiver }
+    }
+
+    /// Run the blocking work `f` as a task, returning a handle to its output.
+    ///
+    /// Aborting the task stops waiting for `f` but doesn't interrupt it.
+    fn spawn_blocking<F, T>(&self, f: F) -> TaskHandle<T>
+    where
+        F: FnOnce() -> T + Send + 'static,
+        T: Send + 'static,
+    {
+        let (sender, receiver) = oneshot::channel();
+        let blocking = self.spawn_blocking_boxed(Box::new(move || {
+            let _ = sender.send(panic::catch_unwind(AssertUnwindSafe(f)));
+        }));
+
+        self.spawn(async move {
+            blocking.await;
+
+            match receiver.await {
+                Ok(Ok(output)) => output,
+                // The panic is caught again, and reported, by the task.
+                Ok(Err(payload)) => panic::resume_unwind(payload),
+                Err(_) => panic!("blocking work was dropped before completion"),
+            }
+        })
+    }
+
+    /// Create a future that completes after `duration` on the clock of the executor.
+    fn sleep(&self, duration: Duration) -> BoxFuture<'static, ()> {
+        self.sleep_until(self.now() + duration)
+    }
+}
+
+impl<E: Executor + ?Sized> ExecutorExt for E {}
+
+/// A handle to a task spawned with [`ExecutorExt::spawn`], that resolves to the output of the task.
+pub struct TaskHandle<T> {
+    abort: AbortHandle,
+    receiver: oneshot::Receiver<Result<T, JoinError>>,
+}
+
+impl<T> TaskHandle<T> {
+    /// Abort the task, which is dropped the next time it would have been polled.
+    pub fn abort(&self) {
+        self.abort.abort();
+    }
+}
+
+impl<T> Future for TaskHandle<T> {
+    type Output = Result<T, JoinError>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        self.receiver
+            .poll_unpin(cx)
+            .map(|result| result.unwrap_or(Err(JoinError::Aborted)))
+    }
+}
+
+fn panic_message(payload: Box<dyn Any + Send>) -> String {
+    match payload.downcast::<String>() {
+        Ok(message) => *message,
+        Err(payload) => match payload.downcast::<&'static str>() {
+            Ok(message) => (*message).to_owned(),
+            Err(_) => "unknown panic payload".to_owned(),
+        },
+    }
+}
+
+/// An executor that runs tasks on the ambient tokio runtime and blocking work on its blocking thread pool.
+///
+/// Its clock is the tokio clock, such that paused and virtual time also apply to node tasks.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct TokioExecutor;
+
+impl Executor for TokioExecutor {
+    fn spawn_boxed(&self, future: BoxFuture<'static, ()>) {
+        tokio::spawn(future);
+    }
+
+    fn spawn_blocking_boxed(&self, f: Box<dyn FnOnce() + Send>) -> BoxFuture<'static, ()> {
+        let handle = tokio::task::spawn_blocking(f);
+
+        Box::pin(async move {
+            let _ = handle.await;
+        })
+    }
+
+    fn now(&self) -> Instant {
+        tokio::time::Instant::now().into_std()
+    }
+
+    fn sleep_until(&self, deadline: Instant) -> BoxFuture<'static, ()> {
+        Box::pin(tokio::time::sleep_until(deadline.into()))
+    }
+}
@@ -0,0 +1,170 @@
+// Copyright 2020-2021 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+//! A module that builds and validates the dependency graph of node workers.
+
+use crate::{node::Node, worker::Worker};
+
+use thiserror::Error;
+
+use std::{
+    any::{type_name, TypeId},
+    collections::{HashMap, HashSet},
+    fmt::Write,
+};
+
+/// Errors that may occur when validating a worker dependency graph.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum Error {
+    /// The dependencies of the listed workers form a cycle. The first worker depends on the second one, and so on, with
+    /// the last worker depending on the first one.
+    #[error("Workers have a cyclic dependency: {}.", .0.join(" -> "))]
+    Cycle(Vec<&'static str>),
+    /// A worker depends on another worker that was never registered.
+    #[error("Worker `{worker}` depends on a worker that was not registered: {dependency:?}.")]
+    MissingDependency {
+        /// The name of the worker that has the missing dependency.
+        worker: &'static str,
+        /// The `TypeId` of the missing dependency.
+        dependency: TypeId,
+    },
+}
+
+struct Vertex {
+    id: TypeId,
+    name: &'static str,
+    deps: &'static [TypeId],
+}
+
+/// The dependency graph of a set of workers, as described by [`Worker::dependencies`].
+#[derive(Default)]
+pub struct WorkerGraph {
+    vertices: Vec<Vertex>,
+}
+
+impl WorkerGraph {
+    /// Create a new, empty, worker graph.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add the worker `W` and its dependencies to the graph, replacing any previous entry for `W`.
+    pub fn add_worker<N: Node, W: Worker<N>>(&mut self) {
+        self.add_raw(TypeId::of::<W>(), type_name::<W>(), W::dependencies());
+    }
+
+    /// Add a worker to the graph by its `TypeId`, name and dependencies, replacing any previous entry with this `TypeId`.
+    pub fn add_raw(&mut self, id: TypeId, name: &'static str, deps: &'static [TypeId]) {
+        self.vertices.retain(|vertex| vertex.id != id);
+        self.vertices.push(Vertex { id, name, deps });
+    }
+
+    /// Whether the graph contains the worker with the given `TypeId`.
+    pub fn contains(&self, id: TypeId) -> bool {
+        self.vertices.iter().any(|vertex| vertex.id == id)
+    }
+
+    /// Get the name of the worker with the given `TypeId`, if it is part of the graph.
+    pub fn name(&self, id: TypeId) -> Option<&'static str> {
+        self.vertices
+            .iter()
+            .find(|vertex| vertex.id == id)
+            .map(|vertex| vertex.name)
+    }
+
+    /// Check that every dependency was registered and that there is no dependency cycle.
+    pub fn validate(&self) -> Result<(), Error> {
+        self.startup_order().map(drop)
+    }
+
+    /// Compute the order in which the workers should be started, such that every worker comes after all of its
+    /// dependencies. Ties are broken by the order in which workers were added to the graph.
+    pub fn startup_order(&self) -> Result<Vec<TypeId>, Error> {
+        let vertices = self
+            .vertices
+            .iter()
+            .map(|vertex| (vertex.id, vertex))
+            .collect::<HashMap<_, _>>();
+
+        for vertex in self.vertices.iter() {
+            if let Some(dependency) = vertex.deps.iter().find(|dep| !vertices.contains_key(dep)) {
+                return Err(Error::MissingDependency {
+                    worker: vertex.name,
+                    dependency: *dependency,
+                });
+            }
+        }
+
+        let mut order = Vec::with_capacity(self.vertices.len());
+        let mut done = HashSet::new();
+
+        for vertex in self.vertices.iter() {
+            visit(vertex.id, &vertices, &mut Vec::new(), &mut done, &mut order)?;
+        }
+
+        Ok(order)
+    }
+
+    /// Render the graph in the DOT format, with edges pointing from each worker to its dependencies and nodes labelled
+    /// with their position in the startup order when there is one.
+    pub fn to_dot(&self) -> String {
+        let positions = self
+            .startup_order()
+            .map(|order| order.into_iter().zip(1..).collect::<HashMap<_, usize>>())
+            .unwrap_or_default();
+        let mut dot = String::from("digraph workers {\n");
+
+        for vertex in self.vertices.iter() {
+            let label = match positions.get(&vertex.id) {
+                Some(position) => format!("{}. {}", position, vertex.name),
+                None => vertex.name.to_owned(),
+            };
+            // Writing to a `String` can't fail.
+            writeln!(dot, "    \"{}\" [label=\"{}\"];", escape(vertex.name), escape(&label)).unwrap();
+        }
+
+        for vertex in self.vertices.iter() {
+            for dep in vertex.deps.iter() {
+                let dep_name = match self.name(*dep) {
+                    Some(name) => escape(name),
+                    None => format!("{:?}", dep),
+                };
+                writeln!(dot, "    \"{}\" -> \"{}\";", escape(vertex.name), dep_name).unwrap();
+            }
+        }
+
+        dot.push('}');
+        dot.push('\n');
+        dot
+    }
+}
+
+fn visit(
+    id: TypeId,
+    vertices: &HashMap<TypeId, &Vertex>,
+    path: &mut Vec<TypeId>,
+    done: &mut HashSet<TypeId>,
+    order: &mut Vec<TypeId>,
+) -> Result<(), Error> {
+    if done.contains(&id) {
+        return Ok(());
+    }
+    if let Some(start) = path.iter().position(|visiting| *visiting == id) {
+        return Err(Error::Cycle(path[start..].iter().map(|id| vertices[id].name).collect()));
+    }
+
+    path.push(id);
+    for dep in vertices[&id].deps.iter() {
+        visit(*dep, vertices, path, done, order)?;
+    }
+    path.pop();
+
+    done.insert(id);
+    order.push(id);
+
+    Ok(())
+}
+
+fn escape(s: &str) -> String {
+    s.replace('\\', "\\\\").replace('"', "\\\"")
+}
@@ -5,8 +5,23 @@
 
 #![deny(missing_docs, warnings)]
 
+pub mod cancellation;
+pub mod config;
 pub mod event;
+pub mod executor;
+pub mod graph;
+pub mod mailbox;
+pub mod metrics;
 pub mod node;
+pub mod reload;
 pub mod resource;
+pub mod schedule;
+pub mod shutdown_future;
 pub mod shutdown_stream;
+pub mod signal;
+#[cfg(feature = "simulation")]
+pub mod simulation;
+pub mod status;
+pub mod storage;
+pub mod supervisor;
 pub mod worker;
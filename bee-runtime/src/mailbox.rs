@@ -0,0 +1,144 @@
+// Copyright 2020-2021 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+//! A module that provides typed, bounded mailboxes through which workers exchange messages.
+//!
+//! A worker opens a mailbox for a message type when it starts and handles its messages in a task of its own:
+//! ```ignore
+//! mailbox::open::<Self, _, _, _, _>(node, 64, |GetTip| async move { tangle.tip() });
+//! ```
+//! and other workers, depending on it, send messages to it or ask it for a response through its [`Address`]:
+//! ```ignore
+//! let address = Address::<GetTip>::clone(&node.resource());
+//! let tip = address.ask(GetTip).await?;
+//! ```
+
+use crate::{node::Node, shutdown_stream::ShutdownStream, worker::Worker};
+
+use futures::{channel::oneshot, future::Future, stream, StreamExt};
+use thiserror::Error;
+use tokio::sync::mpsc;
+
+/// A message that can be sent to a mailbox.
+pub trait Message: Send + 'static {
+    /// The type of the response to the message, `()` for messages that are only ever sent.
+    type Response: Send + 'static;
+}
+
+/// Errors that may occur when sending a message to a mailbox.
+#[derive(Error, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Error {
+    /// The mailbox is closed, its worker has been stopped or the message was dropped unhandled as it was stopping.
+    #[error("The mailbox is closed.")]
+    Closed,
+    /// The mailbox is full.
+    #[error("The mailbox is full.")]
+    Full,
+}
+
+struct Envelope<M: Message> {
+    message: M,
+    responder: Option<oneshot::Sender<M::Response>>,
+}
+
+/// A cloneable address of a mailbox, through which messages of type `M` are sent to the worker owning it.
+///
+/// The address of a mailbox is registered as a node resource when it is opened. Addresses of a worker that has been
+/// stopped are closed, even if the worker is started again, and must be obtained anew from the node.
+pub struct Address<M: Message> {
+    sender: mpsc::Sender<Envelope<M>>,
+}
+
+impl<M: Message> Clone for Address<M> {
+    fn clone(&self) -> Self {
+        Self {
+            sender: self.sender.clone(),
+        }
+    }
+}
+
+impl<M: Message> Address<M> {
+    /// Send a message, waiting for room in the mailbox if it is full, and without waiting for it to be handled.
+    pub async fn send(&self, message: M) -> Result<(), Error> {
+        self.sender
+            .send(Envelope {
+                message,
+                responder: None,
+            })
+            .await
+            .map_err(|_| Error::Closed)
+    }
+
+    /// Send a message if there is room in the mailbox, without waiting for it to be handled.
+    pub fn try_send(&self, message: M) -> Result<(), Error> {
+        self.sender
+            .try_send(Envelope {
+                message,
+                responder: None,
+            })
+            .map_err(|e| match e {
+                mpsc::error::TrySendError::Full(_) => Error::Full,
+                mpsc::error::TrySendError::Closed(_) => Error::Closed,
+            })
+    }
+
+    /// Send a message, waiting for room in the mailbox if it is full, and wait for its response.
+    pub async fn ask(&self, message: M) -> Result<M::Response, Error> {
+        let (responder, response) = oneshot::channel();
+
+        self.sender
+            .send(Envelope {
+                message,
+                responder: Some(responder),
+            })
+            .await
+            .map_err(|_| Error::Closed)?;
+
+        response.await.map_err(|_| Error::Closed)
+    }
+
+    /// Whether the mailbox is closed.
+    pub fn is_closed(&self) -> bool {
+        self.sender.is_closed()
+    }
+}
+
+/// Open a mailbox of `capacity` messages of type `M` for the worker `W`, register its [`Address`] as a node resource and
+/// return it.
+///
+/// Messages are handled one at a time by `handler`, in a task of the worker. The mailbox is closed when the worker is
+/// shut down, before [`Worker::stop`] is called: the message being handled, if any, is handled to completion, but
+/// messages that are still queued are dropped and their senders get an [`Error::Closed`].
+///
+/// # Panics
+///
+/// Panics if `capacity` is zero.
+pub fn open<W, N, M, H, Fut>(node: &mut N, capacity: usize, mut handler: H) -> Address<M>
+where
+    W: Worker<N>,
+    N: Node,
+    M: Message,
+    H: FnMut(M) -> Fut + Send + 'static,
+    Fut: Future<Output = M::Response> + Send,
+{
+    assert!(capacity > 0, "the capacity of a mailbox must be non-zero");
+
+    let (sender, mut receiver) = mpsc::channel::<Envelope<M>>(capacity);
+    let address = Address { sender };
+
+    node.register_resource(address.clone());
+    node.spawn::<W, _, _>(|shutdown| async move {
+        let mut envelopes = ShutdownStream::new(shutdown, stream::poll_fn(move |cx| receiver.poll_recv(cx)));
+
+        while let Some(Envelope { message, responder }) = envelopes.next().await {
+            let response = handler(message).await;
+
+            if let Some(responder) = responder {
+                // The asking side may have given up on the response.
+                let _ = responder.send(response);
+            }
+        }
+    });
+
+    address
+}
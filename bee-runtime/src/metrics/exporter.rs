@@ -0,0 +1,163 @@
+// Copyright 2020-2021 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    config::ConfiguredWorker, metrics::MetricsRegistry, node::Node, shutdown_stream::ShutdownStream, worker::Worker,
+};
+
+use async_trait::async_trait;
+use futures::{stream, StreamExt};
+use log::{debug, info, warn};
+use serde::Deserialize;
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::{TcpListener, TcpStream},
+};
+
+use std::{
+    io,
+    net::{Ipv4Addr, SocketAddr},
+    time::Duration,
+};
+
+const DEFAULT_PORT: u16 = 9311;
+const MAX_REQUEST_SIZE: usize = 8 * 1024;
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Builder for a [`MetricsExporterConfig`].
+#[derive(Default, Deserialize)]
+pub struct MetricsExporterConfigBuilder {
+    bind_address: Option<SocketAddr>,
+}
+
+impl MetricsExporterConfigBuilder {
+    /// Creates a new builder for a metrics exporter configuration.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the address the exporter listens on.
+    pub fn bind_address(mut self, address: SocketAddr) -> Self {
+        self.bind_address.replace(address);
+        self
+    }
+
+    /// Builds a metrics exporter configuration.
+    pub fn finish(self) -> MetricsExporterConfig {
+        MetricsExporterConfig {
+            bind_address: self
+                .bind_address
+                .unwrap_or_else(|| (Ipv4Addr::LOCALHOST, DEFAULT_PORT).into()),
+        }
+    }
+}
+
+impl From<MetricsExporterConfigBuilder> for MetricsExporterConfig {
+    fn from(builder: MetricsExporterConfigBuilder) -> Self {
+        builder.finish()
+    }
+}
+
+/// Configuration of a [`MetricsExporter`].
+#[derive(Clone, Debug)]
+pub struct MetricsExporterConfig {
+    /// The address the exporter listens on, `127.0.0.1:9311` by default.
+    pub bind_address: SocketAddr,
+}
+
+impl Default for MetricsExporterConfig {
+    fn default() -> Self {
+        MetricsExporterConfigBuilder::new().finish()
+    }
+}
+
+/// A worker that serves the [`MetricsRegistry`] of the node in the Prometheus text format, at
+/// `http://<bind_address>/metrics`.
+pub struct MetricsExporter {
+    local_addr: SocketAddr,
+}
+
+impl MetricsExporter {
+    /// Get the address the exporter actually listens on, e.g. when bound to port `0`.
+    pub fn local_addr(&self) -> SocketAddr {
+        self.local_addr
+    }
+}
+
+#[async_trait]
+impl<N: Node> Worker<N> for MetricsExporter {
+    type Config = MetricsExporterConfig;
+    type Error = io::Error;
+
+    async fn start(node: &mut N, config: Self::Config) -> Result<Self, Self::Error> {
+        let listener = TcpListener::bind(config.bind_address).await?;
+        let local_addr = listener.local_addr()?;
+        let metrics = node.resource::<MetricsRegistry>();
+
+        node.spawn::<Self, _, _>(|shutdown| async move {
+            let mut connections =
+                ShutdownStream::new(shutdown, stream::poll_fn(move |cx| listener.poll_accept(cx).map(Some)));
+
+            // Connections are served one at a time, scrapes being both rare and cheap.
+            while let Some(connection) = connections.next().await {
+                match connection {
+                    Ok((stream, peer)) => match tokio::time::timeout(REQUEST_TIMEOUT, serve(stream, &metrics)).await {
+                        Ok(Ok(())) => {}
+                        Ok(Err(e)) => debug!("Serving metrics to {} failed: {}.", peer, e),
+                        Err(_) => debug!("Serving metrics to {} timed out.", peer),
+                    },
+                    Err(e) => warn!("Accepting a metrics connection failed: {}.", e),
+                }
+            }
+        });
+
+        info!("Serving metrics on http://{}/metrics.", local_addr);
+
+        Ok(Self { local_addr })
+    }
+}
+
+impl<N: Node> ConfiguredWorker<N> for MetricsExporter {
+    const SECTION: &'static str = "metrics";
+
+    type ConfigBuilder = MetricsExporterConfigBuilder;
+}
+
+async fn serve(mut stream: TcpStream, metrics: &MetricsRegistry) -> io::Result<()> {
+    let mut request = Vec::new();
+    let mut buffer = [0; 1024];
+
+    // Only the request line matters, the headers are read and ignored.
+    while !request.windows(4).any(|window| window == b"\r\n\r\n") {
+        if request.len() > MAX_REQUEST_SIZE {
+            return respond(&mut stream, "431 Request Header Fields Too Large", "").await;
+        }
+
+        let read = stream.read(&mut buffer).await?;
+        if read == 0 {
+            return Ok(());
+        }
+        request.extend_from_slice(&buffer[..read]);
+    }
+
+    let request = String::from_utf8_lossy(&request);
+    let mut request_line = request.split_whitespace();
+
+    match (request_line.next(), request_line.next()) {
+        (Some("GET"), Some("/metrics")) => respond(&mut stream, "200 OK", &metrics.render()).await,
+        (Some("GET"), _) => respond(&mut stream, "404 Not Found", "").await,
+        _ => respond(&mut stream, "405 Method Not Allowed", "").await,
+    }
+}
+
+async fn respond(stream: &mut TcpStream, status: &str, body: &str) -> io::Result<()> {
+    let response = format!(
+        "HTTP/1.1 {}\r\nContent-Type: text/plain; version=0.0.4\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        body.len(),
+        body
+    );
+
+    stream.write_all(response.as_bytes()).await?;
+    stream.shutdown().await
+}
@@ -0,0 +1,418 @@
+// Copyright 2020-2021 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+//! A module that provides a registry of node metrics, rendered in the Prometheus text format.
+//!
+//! [`BeeNode`](crate::node::BeeNode)s register a [`MetricsRegistry`] as a resource, instrument their
+//! [`Bus`](crate::event::Bus) and resources with it, and workers add their own metrics, labelled by worker:
+//! ```ignore
+//! let processed = node.resource::<MetricsRegistry>().worker::<Self>().counter("bee_messages_processed_total", "...");
+//!
+//! processed.inc();
+//! ```
+//! The [`MetricsExporter`] worker serves the registry over HTTP.
+
+mod exporter;
+
+pub use exporter::{MetricsExporter, MetricsExporterConfig, MetricsExporterConfigBuilder};
+
+use dashmap::DashMap;
+
+use std::{
+    any::{type_name, Any, TypeId},
+    collections::BTreeMap,
+    fmt::{self, Write},
+    sync::{
+        atomic::{AtomicI64, AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+};
+
+/// A metric that can only go up.
+#[derive(Clone, Debug, Default)]
+pub struct Counter(Arc<AtomicU64>);
+
+impl Counter {
+    /// Increment the counter by one.
+    pub fn inc(&self) {
+        self.inc_by(1);
+    }
+
+    /// Increment the counter by the given amount.
+    pub fn inc_by(&self, amount: u64) {
+        self.0.fetch_add(amount, Ordering::Relaxed);
+    }
+
+    /// Get the value of the counter.
+    pub fn get(&self) -> u64 {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// A metric that can go up and down.
+#[derive(Clone, Debug, Default)]
+pub struct Gauge(Arc<AtomicI64>);
+
+impl Gauge {
+    /// Set the value of the gauge.
+    pub fn set(&self, value: i64) {
+        self.0.store(value, Ordering::Relaxed);
+    }
+
+    /// Add the given amount, that may be negative, to the gauge.
+    pub fn add(&self, amount: i64) {
+        self.0.fetch_add(amount, Ordering::Relaxed);
+    }
+
+    /// Increment the gauge by one.
+    pub fn inc(&self) {
+        self.add(1);
+    }
+
+    /// Decrement the gauge by one.
+    pub fn dec(&self) {
+        self.add(-1);
+    }
+
+    /// Get the value of the gauge.
+    pub fn get(&self) -> i64 {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+#[derive(Debug)]
+struct HistogramState {
+    bounds: Vec<f64>,
+    // The number of observations in each bucket, the last one being `+Inf`.
+    buckets: Vec<u64>,
+    sum: f64,
+    count: u64,
+}
+
+/// A metric that samples observations into buckets.
+#[derive(Clone, Debug)]
+pub struct Histogram(Arc<Mutex<HistogramState>>);
+
+impl Histogram {
+    fn new(bounds: &[f64]) -> Self {
+        let mut bounds = bounds.to_vec();
+        bounds.sort_by(|a, b| a.total_cmp(b));
+        bounds.dedup();
+
+        Self(Arc::new(Mutex::new(HistogramState {
+            buckets: vec![0; bounds.len() + 1],
+            bounds,
+            sum: 0.0,
+            count: 0,
+        })))
+    }
+
+    /// Record an observation.
+    pub fn observe(&self, value: f64) {
+        let mut state = self.0.lock().unwrap();
+        let bucket = state.bounds.iter().take_while(|bound| value > **bound).count();
+
+        state.buckets[bucket] += 1;
+        state.sum += value;
+        state.count += 1;
+    }
+
+    /// Get the number of observations.
+    pub fn count(&self) -> u64 {
+        self.0.lock().unwrap().count
+    }
+
+    /// Get the sum of all the observations.
+    pub fn sum(&self) -> f64 {
+        self.0.lock().unwrap().sum
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Kind {
+    Counter,
+    Gauge,
+    Histogram,
+}
+
+impl fmt::Display for Kind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let kind = match self {
+            Self::Counter => "counter",
+            Self::Gauge => "gauge",
+            Self::Histogram => "histogram",
+        };
+
+        write!(f, "{}", kind)
+    }
+}
+
+type Computed = Arc<dyn Fn() -> Option<i64> + Send + Sync>;
+
+#[derive(Clone)]
+enum Series {
+    Counter(Counter),
+    Gauge(Gauge),
+    Computed(Computed),
+    Histogram(Histogram),
+}
+
+type Labels = Vec<(String, String)>;
+
+struct Family {
+    help: String,
+    kind: Kind,
+    series: BTreeMap<Labels, Series>,
+}
+
+/// A registry of counters, gauges and histograms, identified by their name and labels.
+///
+/// Getting a metric that doesn't exist yet creates it, getting it again returns the same metric.
+#[derive(Clone, Default)]
+pub struct MetricsRegistry {
+    families: Arc<Mutex<BTreeMap<String, Family>>>,
+}
+
+impl MetricsRegistry {
+    /// Create an empty registry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Get the counter with the given name and labels.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a metric of another type was registered with the same name.
+    pub fn counter(&self, name: &str, help: &str, labels: &[(&str, &str)]) -> Counter {
+        match self.series(
+            name,
+            help,
+            Kind::Counter,
+            labels,
+            || Series::Counter(Counter::default()),
+        ) {
+            Series::Counter(counter) => counter,
+            _ => unreachable!(),
+        }
+    }
+
+    /// Get the gauge with the given name and labels.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a metric of another type, or a computed gauge with the same labels, was registered with the same name.
+    pub fn gauge(&self, name: &str, help: &str, labels: &[(&str, &str)]) -> Gauge {
+        match self.series(name, help, Kind::Gauge, labels, || Series::Gauge(Gauge::default())) {
+            Series::Gauge(gauge) => gauge,
+            _ => panic!("Gauge `{}` is computed.", name),
+        }
+    }
+
+    /// Register a gauge with the given name and labels whose value is computed by `f` whenever the registry is
+    /// rendered, replacing the previous one. The gauge is left out while `f` returns `None`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a metric of another type was registered with the same name.
+    pub fn computed_gauge<F>(&self, name: &str, help: &str, labels: &[(&str, &str)], f: F)
+    where
+        F: Fn() -> Option<i64> + Send + Sync + 'static,
+    {
+        let mut families = self.families.lock().unwrap();
+
+        family(&mut families, name, help, Kind::Gauge)
+            .series
+            .insert(to_labels(labels), Series::Computed(Arc::new(f)));
+    }
+
+    /// Get the histogram with the given name and labels, sampling observations into buckets with the given upper
+    /// bounds if it doesn't exist yet.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a metric of another type was registered with the same name.
+    pub fn histogram(&self, name: &str, help: &str, labels: &[(&str, &str)], bounds: &[f64]) -> Histogram {
+        match self.series(name, help, Kind::Histogram, labels, || {
+            Series::Histogram(Histogram::new(bounds))
+        }) {
+            Series::Histogram(histogram) => histogram,
+            _ => unreachable!(),
+        }
+    }
+
+    /// Get a view of the registry whose metrics are labelled by the worker `W`.
+    pub fn worker<W: Any>(&self) -> WorkerMetrics {
+        WorkerMetrics {
+            registry: self.clone(),
+            worker: type_name::<W>(),
+        }
+    }
+
+    /// Render all the metrics in the Prometheus text exposition format, sorted by name and labels.
+    pub fn render(&self) -> String {
+        let families = self.families.lock().unwrap();
+        let mut text = String::new();
+
+        for (name, family) in families.iter() {
+            // Writing to a `String` can't fail.
+            writeln!(text, "# HELP {} {}", name, escape(&family.help, false)).unwrap();
+            writeln!(text, "# TYPE {} {}", name, family.kind).unwrap();
+
+            for (labels, series) in family.series.iter() {
+                match series {
+                    Series::Counter(counter) => sample(&mut text, name, labels, None, counter.get()),
+                    Series::Gauge(gauge) => sample(&mut text, name, labels, None, gauge.get()),
+                    Series::Computed(f) => {
+                        if let Some(value) = f() {
+                            sample(&mut text, name, labels, None, value);
+                        }
+                    }
+                    Series::Histogram(histogram) => {
+                        let state = histogram.0.lock().unwrap();
+                        let bucket = format!("{}_bucket", name);
+                        let mut cumulative = 0;
+
+                        for (bound, count) in state.bounds.iter().zip(state.buckets.iter()) {
+                            cumulative += count;
+                            sample(&mut text, &bucket, labels, Some(&bound.to_string()), cumulative);
+                        }
+                        sample(&mut text, &bucket, labels, Some("+Inf"), state.count);
+                        sample(&mut text, &format!("{}_sum", name), labels, None, state.sum);
+                        sample(&mut text, &format!("{}_count", name), labels, None, state.count);
+                    }
+                }
+            }
+        }
+
+        text
+    }
+
+    fn series(
+        &self,
+        name: &str,
+        help: &str,
+        kind: Kind,
+        labels: &[(&str, &str)],
+        create: impl FnOnce() -> Series,
+    ) -> Series {
+        let mut families = self.families.lock().unwrap();
+
+        family(&mut families, name, help, kind)
+            .series
+            .entry(to_labels(labels))
+            .or_insert_with(create)
+            .clone()
+    }
+}
+
+/// A view of a [`MetricsRegistry`] whose metrics are labelled by a worker.
+#[derive(Clone)]
+pub struct WorkerMetrics {
+    registry: MetricsRegistry,
+    worker: &'static str,
+}
+
+impl WorkerMetrics {
+    /// Get the counter of the worker with the given name, like [`MetricsRegistry::counter`].
+    pub fn counter(&self, name: &str, help: &str) -> Counter {
+        self.registry.counter(name, help, &[("worker", self.worker)])
+    }
+
+    /// Get the gauge of the worker with the given name, like [`MetricsRegistry::gauge`].
+    pub fn gauge(&self, name: &str, help: &str) -> Gauge {
+        self.registry.gauge(name, help, &[("worker", self.worker)])
+    }
+
+    /// Get the histogram of the worker with the given name, like [`MetricsRegistry::histogram`].
+    pub fn histogram(&self, name: &str, help: &str, bounds: &[f64]) -> Histogram {
+        self.registry.histogram(name, help, &[("worker", self.worker)], bounds)
+    }
+}
+
+/// Counts the events dispatched on a [`Bus`](crate::event::Bus) by event type.
+pub(crate) struct DispatchCounters {
+    registry: MetricsRegistry,
+    counters: DashMap<TypeId, Counter>,
+}
+
+impl DispatchCounters {
+    pub(crate) fn new(registry: MetricsRegistry) -> Self {
+        Self {
+            registry,
+            counters: DashMap::new(),
+        }
+    }
+
+    pub(crate) fn inc<E: Any>(&self) {
+        self.counters
+            .entry(TypeId::of::<E>())
+            .or_insert_with(|| {
+                self.registry.counter(
+                    "bee_bus_dispatches_total",
+                    "Number of events dispatched on the event bus.",
+                    &[("event", type_name::<E>())],
+                )
+            })
+            .inc();
+    }
+}
+
+fn family<'a>(families: &'a mut BTreeMap<String, Family>, name: &str, help: &str, kind: Kind) -> &'a mut Family {
+    let family = families.entry(name.to_owned()).or_insert_with(|| Family {
+        help: help.to_owned(),
+        kind,
+        series: BTreeMap::new(),
+    });
+
+    assert!(
+        family.kind == kind,
+        "Metric `{}` is already registered as a {}.",
+        name,
+        family.kind
+    );
+
+    family
+}
+
+fn to_labels(labels: &[(&str, &str)]) -> Labels {
+    let mut labels = labels
+        .iter()
+        .map(|(name, value)| (name.to_string(), value.to_string()))
+        .collect::<Vec<_>>();
+
+    labels.sort();
+    labels
+}
+
+fn sample<V: fmt::Display>(text: &mut String, name: &str, labels: &Labels, le: Option<&str>, value: V) {
+    text.push_str(name);
+
+    if !labels.is_empty() || le.is_some() {
+        let labels = labels
+            .iter()
+            .map(|(name, value)| (name.as_str(), value.as_str()))
+            .chain(le.map(|le| ("le", le)))
+            .map(|(name, value)| format!("{}=\"{}\"", name, escape(value, true)))
+            .collect::<Vec<_>>();
+
+        write!(text, "{{{}}}", labels.join(",")).unwrap();
+    }
+
+    writeln!(text, " {}", value).unwrap();
+}
+
+fn escape(text: &str, quotes: bool) -> String {
+    let mut escaped = String::with_capacity(text.len());
+
+    for c in text.chars() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '"' if quotes => escaped.push_str("\\\""),
+            c => escaped.push(c),
+        }
+    }
+
+    escaped
+}
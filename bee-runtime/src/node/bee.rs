@@ -0,0 +1,625 @@
+// Copyright 2020-2021 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    cancellation::CancellationToken,
+    config::Config,
+    executor::{Executor, ExecutorExt, TaskHandle},
+    metrics::MetricsRegistry,
+    node::{
+        builder::{start_worker, BeeNodeBuilder},
+        BeeNodeConfig, Error, Node, ReloadReport, StopOverrun, StopReport,
+    },
+    resource::{ResourceHandle, ResourceReport},
+    status::{StatusRegistry, WorkerStatus},
+    supervisor::{self, RestartPolicy},
+    worker::{self, Worker},
+};
+
+use bee_storage::backend::StorageBackend;
+
+use async_trait::async_trait;
+use futures::{
+    channel::oneshot,
+    future::{self, BoxFuture, Either, Future, LocalBoxFuture},
+};
+use log::{debug, error, warn};
+use tracing::{info_span, Instrument};
+
+use std::{
+    any::{type_name, Any, TypeId},
+    collections::HashMap,
+    marker::PhantomData,
+    sync::Arc,
+};
+
+trait AnyResource: Any + Send + Sync {
+    fn as_any(&self) -> &dyn Any;
+
+    fn into_any(self: Box<Self>) -> Box<dyn Any>;
+
+    fn report(&self) -> ResourceReport;
+}
+
+impl<R: Any + Send + Sync> AnyResource for ResourceHandle<R> {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn into_any(self: Box<Self>) -> Box<dyn Any> {
+        self
+    }
+
+    fn report(&self) -> ResourceReport {
+        ResourceHandle::report(self)
+    }
+}
+
+pub(crate) type WorkerStop<N> = dyn for<'a> FnOnce(&'a mut N) -> BoxFuture<'a, Result<(), Error>> + Send + Sync;
+
+/// Starts a stopped worker again, configured workers with the configuration of their section in the given
+/// configuration.
+pub(crate) type WorkerRestart<N> = for<'a, 'b> fn(&'a mut N, &'b Config) -> LocalBoxFuture<'a, Result<(), Error>>;
+
+/// How to apply a new configuration to a worker that reads its configuration from a [`Config`] section.
+pub(crate) struct WorkerReload<N> {
+    pub(crate) name: &'static str,
+    pub(crate) section: String,
+    /// Reconfigures the running worker with the configuration of its section in the given configuration, returning
+    /// whether it was able to.
+    pub(crate) reconfigure: for<'a, 'b> fn(&'a mut N, &'b Config) -> LocalBoxFuture<'a, Result<bool, Error>>,
+}
+
+/// A node that starts its workers in topological order and stops them in reverse order.
+///
+/// Tasks created with [`Node::spawn`] are run on the [`Executor`] of the node, the ambient tokio runtime unless another
+/// one is set with [`BeeNodeBuilder::with_executor`], and are shut down, through their `oneshot` channel, right before
+/// the worker that owns them is stopped.
+///
+/// Workers are started and stopped, and their tasks run, inside `tracing` spans named `start`, `stop` and `task`, with a
+/// `worker` field set to the name of the worker type.
+///
+/// Shutdown signals follow a node → worker → task hierarchy of [`CancellationToken`]s: cancelling the token of the node
+/// shuts down every task at once, while cancelling the token of a worker only shuts down the tasks of that worker. When
+/// a stop deadline is configured, workers that take longer than it to stop, tasks included, are force-dropped and
+/// reported in the [`StopReport`].
+///
+/// Workers registered with [`BeeNodeBuilder::with_configured_worker`] follow the configuration given to
+/// [`BeeNode::reload`].
+pub struct BeeNode<B> {
+    config: BeeNodeConfig,
+    config_source: Config,
+    reloads: HashMap<TypeId, WorkerReload<Self>>,
+    restarts: HashMap<TypeId, WorkerRestart<Self>>,
+    token: CancellationToken,
+    worker_tokens: HashMap<TypeId, CancellationToken>,
+    workers: HashMap<TypeId, Box<dyn Any + Send + Sync>>,
+    tasks: HashMap<TypeId, Vec<TaskHandle<()>>>,
+    executor: Arc<dyn Executor>,
+    restart_policies: HashMap<TypeId, RestartPolicy>,
+    statuses: StatusRegistry,
+    metrics: MetricsRegistry,
+    resources: HashMap<TypeId, Box<dyn AnyResource>>,
+    worker_stops: HashMap<TypeId, (&'static str, Box<WorkerStop<Self>>)>,
+    worker_order: Vec<TypeId>,
+    dependencies: HashMap<TypeId, &'static [TypeId]>,
+    worker_resources: HashMap<TypeId, Vec<TypeId>>,
+    starting: Option<TypeId>,
+    phantom: PhantomData<B>,
+}
+
+impl<B: StorageBackend> BeeNode<B> {
+    pub(crate) fn new(
+        config: BeeNodeConfig,
+        config_source: Config,
+        reloads: HashMap<TypeId, WorkerReload<Self>>,
+        restarts: HashMap<TypeId, WorkerRestart<Self>>,
+        restart_policies: HashMap<TypeId, RestartPolicy>,
+        metrics: MetricsRegistry,
+        executor: Arc<dyn Executor>,
+    ) -> Self {
+        let statuses = StatusRegistry::default();
+
+        let mut node = Self {
+            config,
+            config_source,
+            reloads,
+            restarts,
+            token: CancellationToken::new(),
+            worker_tokens: HashMap::new(),
+            workers: HashMap::new(),
+            tasks: HashMap::new(),
+            executor,
+            restart_policies,
+            statuses: statuses.clone(),
+            metrics: metrics.clone(),
+            resources: HashMap::new(),
+            worker_stops: HashMap::new(),
+            worker_order: Vec::new(),
+            dependencies: HashMap::new(),
+            worker_resources: HashMap::new(),
+            starting: None,
+            phantom: PhantomData,
+        };
+
+        node.register_resource(statuses);
+        node.register_resource(metrics);
+        node
+    }
+
+    pub(crate) fn statuses(&self) -> &StatusRegistry {
+        &self.statuses
+    }
+
+    pub(crate) fn config_source(&self) -> &Config {
+        &self.config_source
+    }
+
+    /// Set the worker being started, that owns the resources registered in the meantime.
+    pub(crate) fn set_starting_worker(&mut self, id: Option<TypeId>) {
+        self.starting = id;
+    }
+
+    pub(crate) fn insert_worker<W: Worker<Self>>(&mut self, worker: W, stop: Box<WorkerStop<Self>>) {
+        self.restore_worker(worker);
+        self.statuses
+            .set_status(TypeId::of::<W>(), type_name::<W>(), WorkerStatus::Running);
+
+        self.worker_stops.insert(TypeId::of::<W>(), (type_name::<W>(), stop));
+        self.dependencies.insert(TypeId::of::<W>(), W::dependencies());
+        // A restarted worker keeps its original position in the stop order.
+        if !self.worker_order.contains(&TypeId::of::<W>()) {
+            self.worker_order.push(TypeId::of::<W>());
+        }
+    }
+
+    /// Put back a worker that was temporarily taken out with [`BeeNode::take_worker`].
+    pub(crate) fn restore_worker<W: Worker<Self>>(&mut self, worker: W) {
+        let worker = Arc::new(worker);
+        let weak = Arc::downgrade(&worker);
+
+        self.statuses.set_health_check(
+            TypeId::of::<W>(),
+            type_name::<W>(),
+            Some(Box::new(move || weak.upgrade().map(|worker| worker.health()))),
+        );
+        self.workers.insert(TypeId::of::<W>(), Box::new(worker));
+    }
+
+    pub(crate) fn take_worker<W: Worker<Self>>(&mut self) -> Option<W> {
+        // Once removed, the health check can't hold a reference to the worker anymore.
+        self.statuses
+            .set_health_check(TypeId::of::<W>(), type_name::<W>(), None);
+
+        self.workers
+            .remove(&TypeId::of::<W>())
+            .and_then(|worker| worker.downcast::<Arc<W>>().ok())
+            .and_then(|worker| Arc::try_unwrap(*worker).ok())
+    }
+
+    /// Get the cancellation token of the worker `W`, that shuts down all the tasks of this worker when cancelled.
+    pub fn worker_cancellation_token<W: Worker<Self>>(&mut self) -> CancellationToken {
+        self.worker_token(TypeId::of::<W>())
+    }
+
+    fn worker_token(&mut self, id: TypeId) -> CancellationToken {
+        let token = &self.token;
+
+        self.worker_tokens.entry(id).or_insert_with(|| token.child()).clone()
+    }
+
+    /// Report the live handles of every registered resource, sorted by resource type name.
+    pub fn resource_reports(&self) -> Vec<ResourceReport> {
+        let mut reports = self.resources.values().map(|res| res.report()).collect::<Vec<_>>();
+
+        reports.sort_by_key(|report| report.type_name);
+        reports
+    }
+
+    /// Start the worker `W`, with the given configuration, on the running node.
+    ///
+    /// All the dependencies of the worker must be running. The worker is stopped along with the node, before its
+    /// dependencies, unless it is removed before with [`BeeNode::remove_worker`].
+    pub async fn add_worker<W: Worker<Self>>(&mut self, config: W::Config) -> Result<(), Error> {
+        let (id, name) = (TypeId::of::<W>(), type_name::<W>());
+
+        if self.worker_stops.contains_key(&id) {
+            return Err(Error::WorkerRunning(name));
+        }
+        if let Some(dependency) = W::dependencies()
+            .iter()
+            .find(|dep| !self.worker_stops.contains_key(dep))
+        {
+            return Err(match self.statuses.name(*dependency) {
+                Some(dependency) => Error::WorkerDependency(name, dependency),
+                None => Error::UnknownWorkerDependency(name, *dependency),
+            });
+        }
+
+        let result = start_worker::<B, W>(self, config).await;
+
+        if result.is_err() {
+            // Tasks spawned before the failure are shut down, but not waited for.
+            if let Some(token) = self.worker_tokens.remove(&id) {
+                token.cancel();
+            }
+            self.tasks.remove(&id);
+            self.clean_up_worker(id);
+        }
+
+        result
+    }
+
+    /// Stop the worker `W` on the running node and clean up the resources it registered and its [`Bus`] listeners.
+    ///
+    /// No running worker may depend on the removed worker. The report lists the stop deadline overrun of the worker, if
+    /// any, and the resources it registered that are still in use.
+    ///
+    /// [`Bus`]: crate::event::Bus
+    pub async fn remove_worker<W: Worker<Self>>(&mut self) -> Result<StopReport, Error> {
+        let (id, name) = (TypeId::of::<W>(), type_name::<W>());
+
+        if !self.worker_stops.contains_key(&id) {
+            return Err(Error::WorkerNotRunning(name));
+        }
+        let dependent = self.worker_stops.iter().find_map(|(other, (other_name, _))| {
+            matches!(self.dependencies.get(other), Some(deps) if deps.contains(&id)).then(|| *other_name)
+        });
+        if let Some(dependent) = dependent {
+            return Err(Error::WorkerRequired(name, dependent));
+        }
+
+        let mut report = StopReport::default();
+
+        self.worker_order.retain(|other| *other != id);
+        // A removed worker doesn't follow configuration changes anymore, even if it is added again.
+        self.reloads.remove(&id);
+        self.restarts.remove(&id);
+
+        let result = self.stop_worker(id, &mut report).await;
+        report.leaked_resources = self.clean_up_worker(id);
+
+        result.map(|_| report)
+    }
+
+    /// Remove the resources and [`Bus`](crate::event::Bus) listeners of a worker, reporting the resources that are
+    /// still in use.
+    fn clean_up_worker(&mut self, id: TypeId) -> Vec<ResourceReport> {
+        self.bus().remove_listeners_by_id(id);
+        self.dependencies.remove(&id);
+
+        self.worker_resources
+            .remove(&id)
+            .unwrap_or_default()
+            .into_iter()
+            .filter_map(|res| self.resources.remove(&res))
+            .map(|res| res.report())
+            .filter(|report| !report.handles.is_empty())
+            .collect()
+    }
+
+    /// Stop the node, like [`Node::stop`], and report the resources that are still in use once all workers have been
+    /// stopped.
+    ///
+    /// The report is given even if a worker failed to stop, along with the first error that occurred.
+    pub async fn stop_with_report(mut self) -> (StopReport, Option<Error>) {
+        let mut report = StopReport::default();
+
+        let error = self.stop_workers(&mut report).await.err();
+
+        report.leaked_resources = self
+            .resource_reports()
+            .into_iter()
+            .filter(|report| !report.handles.is_empty())
+            .collect();
+
+        (report, error)
+    }
+
+    /// Apply a new configuration to the running node.
+    ///
+    /// The sections of the workers registered with [`BeeNodeBuilder::with_configured_worker`] are compared with the
+    /// current configuration. Workers whose section changed are given their new configuration through
+    /// [`Worker::reconfigure`] and those that can't be reconfigured are restarted, along with the workers that depend on
+    /// them: all of them are stopped in reverse startup order, their resources and [`Bus`] listeners are removed, and
+    /// they are started again in startup order. Workers that depend on a restarted worker must have been registered
+    /// with [`NodeBuilder::with_worker`] or [`BeeNodeBuilder::with_configured_worker`], to be started again.
+    ///
+    /// The new configuration becomes the current one only once it has been applied to every worker. If a worker fails
+    /// to apply it, the node keeps its current configuration, such that reloading the new configuration again applies
+    /// it to every worker whose section changed.
+    ///
+    /// [`Bus`]: crate::event::Bus
+    /// [`NodeBuilder::with_worker`]: crate::node::NodeBuilder::with_worker
+    pub async fn reload(&mut self, config: Config) -> Result<ReloadReport, Error> {
+        let node_config = config.node()?;
+
+        let changed = self
+            .worker_order
+            .iter()
+            .filter(|id| match self.reloads.get(id) {
+                Some(reload) => self.config_source.get(&reload.section) != config.get(&reload.section),
+                None => false,
+            })
+            .copied()
+            .collect::<Vec<_>>();
+        let mut report = ReloadReport::default();
+        let mut restarts = Vec::new();
+
+        for id in changed {
+            let (name, reconfigure) = match self.reloads.get(&id) {
+                Some(reload) => (reload.name, reload.reconfigure),
+                None => continue,
+            };
+
+            debug!("Reconfiguring worker `{}`...", name);
+
+            if reconfigure(self, &config).await? {
+                report.reconfigured.push(name);
+            } else {
+                restarts.push(id);
+            }
+        }
+
+        let restarts = self
+            .with_dependents(&restarts)
+            .into_iter()
+            .filter_map(|id| self.worker_stops.get(&id).map(|(name, _)| (id, *name)))
+            .collect::<Vec<_>>();
+
+        if let Some((_, name)) = restarts.iter().find(|(id, _)| !self.restarts.contains_key(id)) {
+            return Err(Error::WorkerNotRestartable(name));
+        }
+
+        let mut stop_report = StopReport::default();
+
+        for (id, _) in restarts.iter().rev() {
+            self.stop_worker(*id, &mut stop_report).await?;
+            // The worker registers its resources and listeners again once started again.
+            report.leaked_resources.extend(self.clean_up_worker(*id));
+        }
+        report.overruns = stop_report.overruns;
+
+        for (id, name) in restarts {
+            let start = match self.restarts.get(&id) {
+                Some(start) => *start,
+                None => continue,
+            };
+
+            start(self, &config).await?;
+            report.restarted.push(name);
+        }
+
+        self.config = node_config;
+        self.config_source = config;
+
+        Ok(report)
+    }
+
+    /// Extend the given running workers with the running workers that transitively depend on them, in startup order.
+    fn with_dependents(&self, ids: &[TypeId]) -> Vec<TypeId> {
+        let mut with_dependents = Vec::new();
+
+        // Workers come after their dependencies in startup order, so a single pass finds the transitive dependents.
+        for id in self.worker_order.iter() {
+            let dependent = matches!(
+                self.dependencies.get(id),
+                Some(deps) if deps.iter().any(|dep| with_dependents.contains(dep))
+            );
+
+            if ids.contains(id) || dependent {
+                with_dependents.push(*id);
+            }
+        }
+
+        with_dependents
+    }
+
+    /// Stop all started workers, in reverse startup order, returning the first error that occurred.
+    pub(crate) async fn stop_workers(&mut self, report: &mut StopReport) -> Result<(), Error> {
+        let mut result = Ok(());
+
+        while let Some(id) = self.worker_order.pop() {
+            if let Err(e) = self.stop_worker(id, report).await {
+                if result.is_ok() {
+                    result = Err(e);
+                }
+            }
+        }
+
+        self.token.cancel();
+
+        result
+    }
+
+    /// Stop a single worker along with its tasks, within the stop deadline.
+    async fn stop_worker(&mut self, id: TypeId, report: &mut StopReport) -> Result<(), Error> {
+        let (name, stop) = match self.worker_stops.remove(&id) {
+            Some(stop) => stop,
+            None => return Ok(()),
+        };
+
+        self.statuses.set_status(id, name, WorkerStatus::Stopping);
+
+        if let Some(token) = self.worker_tokens.remove(&id) {
+            token.cancel();
+        }
+        let deadline = self.config.stop_deadline;
+        let mut tasks = self.tasks.remove(&id).unwrap_or_default();
+        let mut ended = 0;
+        let timeout = deadline.map(|deadline| self.executor.sleep(deadline));
+
+        let stopping = async {
+            for task in tasks.iter_mut() {
+                if let Err(e) = task.await {
+                    error!("A task of worker `{}` did not end cleanly: {}.", name, e);
+                }
+                ended += 1;
+            }
+
+            stop(self).await
+        };
+
+        let stopped = match (deadline, timeout) {
+            (Some(deadline), Some(timeout)) => match future::select(Box::pin(stopping), timeout).await {
+                Either::Left((stopped, _)) => Ok(stopped),
+                Either::Right(_) => Err(deadline),
+            },
+            _ => Ok(stopping.await),
+        };
+
+        match stopped {
+            Ok(Ok(())) => self.statuses.set_status(id, name, WorkerStatus::Stopped),
+            Ok(Err(e)) => {
+                error!("{}", e);
+                self.statuses.set_status(id, name, WorkerStatus::Failed);
+                self.statuses.set_error(id, name, e.to_string());
+                return Err(e);
+            }
+            Err(deadline) => {
+                for task in tasks.iter().skip(ended) {
+                    task.abort();
+                }
+                // The worker may not have been handed to its stop function yet.
+                self.statuses.set_health_check(id, name, None);
+                self.workers.remove(&id);
+
+                let overrun = StopOverrun {
+                    worker: name,
+                    deadline,
+                    aborted_tasks: tasks.len() - ended,
+                };
+                warn!("{}", overrun);
+                self.statuses.set_status(id, name, WorkerStatus::Failed);
+                self.statuses.set_error(id, name, overrun.to_string());
+                report.overruns.push(overrun);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl<B: StorageBackend> Node for BeeNode<B> {
+    type Builder = BeeNodeBuilder<B>;
+    type Backend = B;
+    type Error = Error;
+
+    async fn stop(mut self) -> Result<(), Self::Error> {
+        let (report, error) = self.stop_with_report().await;
+
+        if !report.is_clean() {
+            warn!("{}", report);
+        }
+
+        error.map_or(Ok(()), Err)
+    }
+
+    fn spawn<W, G, F>(&mut self, g: G)
+    where
+        W: Worker<Self>,
+        G: FnOnce(oneshot::Receiver<()>) -> F,
+        F: Future<Output = ()> + Send + 'static,
+    {
+        let shutdown = self.worker_token(TypeId::of::<W>()).signal();
+        // Tasks outlive the span of the worker start, in which they are usually spawned, so they don't belong to it.
+        let span = info_span!(parent: None, "task", worker = type_name::<W>());
+
+        self.tasks
+            .entry(TypeId::of::<W>())
+            .or_default()
+            .push(self.executor.spawn(g(shutdown).instrument(span)));
+    }
+
+    fn spawn_supervised<W, G, F>(&mut self, g: G)
+    where
+        W: Worker<Self>,
+        G: FnMut(oneshot::Receiver<()>) -> F + Send + 'static,
+        F: Future<Output = Result<(), worker::Error>> + Send + 'static,
+    {
+        let policy = self
+            .restart_policies
+            .get(&TypeId::of::<W>())
+            .cloned()
+            .unwrap_or_default();
+        let executor = self.executor.clone();
+        let bus = self.bus();
+        let statuses = self.statuses.clone();
+
+        self.spawn::<W, _, _>(move |shutdown| {
+            supervisor::supervise::<W, _, _>(policy, executor, bus, statuses, shutdown, g)
+        });
+    }
+
+    fn executor(&self) -> Arc<dyn Executor> {
+        self.executor.clone()
+    }
+
+    fn cancellation_token(&self) -> CancellationToken {
+        self.token.clone()
+    }
+
+    fn worker<W>(&self) -> Option<&W>
+    where
+        W: Worker<Self> + Send + Sync,
+    {
+        self.workers
+            .get(&TypeId::of::<W>())
+            .and_then(|worker| worker.downcast_ref::<Arc<W>>())
+            .map(|worker| worker.as_ref())
+    }
+
+    fn register_resource<R: Any + Send + Sync>(&mut self, res: R) {
+        if let Some(owner) = self.starting {
+            let owned = self.worker_resources.entry(owner).or_default();
+
+            if !owned.contains(&TypeId::of::<R>()) {
+                owned.push(TypeId::of::<R>());
+            }
+        }
+
+        let res = ResourceHandle::new(res);
+
+        self.metrics.computed_gauge(
+            "bee_resource_handles",
+            "Number of live handles to a node resource.",
+            &[("resource", type_name::<R>())],
+            {
+                let count = res.handle_counter();
+                move || count().map(|count| count as i64)
+            },
+        );
+        self.resources.insert(TypeId::of::<R>(), Box::new(res));
+    }
+
+    fn remove_resource<R: Any + Send + Sync>(&mut self) -> Option<R> {
+        let res = self
+            .resources
+            .remove(&TypeId::of::<R>())?
+            .into_any()
+            .downcast::<ResourceHandle<R>>()
+            .ok()?;
+
+        match res.try_take() {
+            Ok(res) => Some(res),
+            Err(res) => {
+                // The resource is still in use, so it stays registered.
+                self.resources.insert(TypeId::of::<R>(), Box::new(res));
+                None
+            }
+        }
+    }
+
+    #[track_caller]
+    fn resource<R: Any + Send + Sync>(&self) -> ResourceHandle<R> {
+        match self
+            .resources
+            .get(&TypeId::of::<R>())
+            .and_then(|res| res.as_any().downcast_ref::<ResourceHandle<R>>())
+        {
+            Some(res) => res.clone(),
+            None => panic!("Unable to fetch node resource `{}`.", type_name::<R>()),
+        }
+    }
+}
@@ -0,0 +1,279 @@
+// Copyright 2020-2021 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    config::{Config, ConfiguredWorker},
+    event::{AsyncBus, Bus},
+    executor::{Executor, TokioExecutor},
+    graph::WorkerGraph,
+    metrics::MetricsRegistry,
+    node::{
+        bee::{BeeNode, WorkerReload, WorkerRestart},
+        BeeNodeConfig, Error, Node, NodeBuilder, StopReport,
+    },
+    status::WorkerStatus,
+    supervisor::RestartPolicy,
+    worker::{self, Worker},
+};
+
+use bee_storage::backend::StorageBackend;
+
+use async_trait::async_trait;
+use futures::future::LocalBoxFuture;
+use log::debug;
+use tracing::{info_span, Instrument};
+
+use std::{
+    any::{type_name, Any, TypeId},
+    collections::HashMap,
+    sync::Arc,
+};
+
+type WorkerStart<N> = dyn for<'a> FnOnce(&'a mut N) -> LocalBoxFuture<'a, Result<(), Error>>;
+type ResourceRegister<N> = dyn FnOnce(&mut N);
+
+/// A builder for [`BeeNode`]s.
+///
+/// Registered workers are started by [`NodeBuilder::finish`] in an order that respects [`Worker::dependencies`].
+pub struct BeeNodeBuilder<B: StorageBackend> {
+    config: BeeNodeConfig,
+    config_source: Config,
+    reloads: HashMap<TypeId, WorkerReload<BeeNode<B>>>,
+    restarts: HashMap<TypeId, WorkerRestart<BeeNode<B>>>,
+    graph: WorkerGraph,
+    worker_starts: HashMap<TypeId, Box<WorkerStart<BeeNode<B>>>>,
+    resource_registers: Vec<Box<ResourceRegister<BeeNode<B>>>>,
+    restart_policies: HashMap<TypeId, RestartPolicy>,
+    metrics: MetricsRegistry,
+    executor: Arc<dyn Executor>,
+}
+
+impl<B: StorageBackend> BeeNodeBuilder<B> {
+    /// Begin building a new node out of a layered configuration, the node itself being configured by the `node`
+    /// section.
+    ///
+    /// Workers registered with [`BeeNodeBuilder::with_configured_worker`] read their own section of this configuration.
+    pub fn from_config(config: Config) -> Result<Self, Error> {
+        let mut builder = Self::new(config.node()?)?;
+
+        builder.config_source = config;
+        Ok(builder)
+    }
+
+    /// Register a worker, configured by its own section of the configuration, that should be started with the node.
+    ///
+    /// Unlike other workers, this worker follows configuration changes applied with [`BeeNode::reload`].
+    pub fn with_configured_worker<W>(mut self) -> Self
+    where
+        W: ConfiguredWorker<BeeNode<B>>,
+        W::Config: Send,
+    {
+        self.graph.add_worker::<BeeNode<B>, W>();
+        self.worker_starts.insert(
+            TypeId::of::<W>(),
+            Box::new(|node: &mut BeeNode<B>| {
+                let config = node.config_source().clone();
+                start_configured_worker::<B, W>(node, &config)
+            }),
+        );
+        self.reloads.insert(
+            TypeId::of::<W>(),
+            WorkerReload {
+                name: type_name::<W>(),
+                section: format!("workers.{}", W::SECTION),
+                reconfigure: reconfigure_worker::<B, W>,
+            },
+        );
+        self.restarts.insert(TypeId::of::<W>(), start_configured_worker::<B, W>);
+        self
+    }
+
+    /// Get the dependency graph of the workers registered so far, e.g. to render it with [`WorkerGraph::to_dot`].
+    pub fn graph(&self) -> &WorkerGraph {
+        &self.graph
+    }
+
+    /// Set the executor that runs the tasks of the node, instead of the ambient tokio runtime.
+    pub fn with_executor<E: Executor>(mut self, executor: E) -> Self {
+        self.executor = Arc::new(executor);
+        self
+    }
+
+    /// Set the restart policy of the tasks that the worker `W` creates with [`Node::spawn_supervised`].
+    ///
+    /// Workers without a restart policy never have their tasks restarted.
+    pub fn with_restart_policy<W: Worker<BeeNode<B>>>(mut self, policy: RestartPolicy) -> Self {
+        self.restart_policies.insert(TypeId::of::<W>(), policy);
+        self
+    }
+}
+
+#[async_trait(?Send)]
+impl<B: StorageBackend> NodeBuilder<BeeNode<B>> for BeeNodeBuilder<B> {
+    type Error = Error;
+    type Config = BeeNodeConfig;
+
+    fn new(config: Self::Config) -> Result<Self, Self::Error> {
+        let metrics = MetricsRegistry::new();
+
+        Ok(Self {
+            config,
+            config_source: Config::default(),
+            reloads: HashMap::new(),
+            restarts: HashMap::new(),
+            graph: WorkerGraph::new(),
+            worker_starts: HashMap::new(),
+            resource_registers: Vec::new(),
+            restart_policies: HashMap::new(),
+            metrics: metrics.clone(),
+            executor: Arc::new(TokioExecutor),
+        }
+        .with_resource(Bus::<TypeId>::with_metrics(metrics))
+        .with_resource(AsyncBus::default()))
+    }
+
+    fn with_worker<W: Worker<BeeNode<B>> + 'static>(self) -> Self
+    where
+        W::Config: Default,
+    {
+        let mut builder = self.with_worker_cfg::<W>(W::Config::default());
+
+        // Unlike workers with a given configuration, the worker can be started again when a dependency is restarted.
+        builder.restarts.insert(TypeId::of::<W>(), start_default_worker::<B, W>);
+        builder
+    }
+
+    fn with_worker_cfg<W: Worker<BeeNode<B>> + 'static>(mut self, config: W::Config) -> Self {
+        self.graph.add_worker::<BeeNode<B>, W>();
+        self.worker_starts.insert(
+            TypeId::of::<W>(),
+            Box::new(|node: &mut BeeNode<B>| Box::pin(start_worker::<B, W>(node, config))),
+        );
+        self
+    }
+
+    fn with_resource<R: Any + Send + Sync>(mut self, res: R) -> Self {
+        self.resource_registers
+            .push(Box::new(move |node: &mut BeeNode<B>| node.register_resource(res)));
+        self
+    }
+
+    async fn finish(mut self) -> Result<BeeNode<B>, Self::Error> {
+        let order = self.graph.startup_order()?;
+        let mut node = BeeNode::new(
+            self.config,
+            self.config_source,
+            self.reloads,
+            self.restarts,
+            self.restart_policies,
+            self.metrics,
+            self.executor,
+        );
+
+        for register in self.resource_registers {
+            register(&mut node);
+        }
+
+        for id in order {
+            let start = match self.worker_starts.remove(&id) {
+                Some(start) => start,
+                None => continue,
+            };
+
+            if let Err(e) = start(&mut node).await {
+                // Tear down whatever has already been started before reporting the failure.
+                let _ = node.stop_workers(&mut StopReport::default()).await;
+                return Err(e);
+            }
+        }
+
+        Ok(node)
+    }
+}
+
+pub(crate) async fn start_worker<B: StorageBackend, W: Worker<BeeNode<B>>>(
+    node: &mut BeeNode<B>,
+    config: W::Config,
+) -> Result<(), Error> {
+    debug!("Starting worker `{}`...", type_name::<W>());
+    node.statuses()
+        .set_status(TypeId::of::<W>(), type_name::<W>(), WorkerStatus::Starting);
+
+    node.set_starting_worker(Some(TypeId::of::<W>()));
+    let worker = W::start(node, config)
+        .instrument(info_span!("start", worker = type_name::<W>()))
+        .await;
+    node.set_starting_worker(None);
+
+    let worker = match worker {
+        Ok(worker) => worker,
+        Err(e) => {
+            let statuses = node.statuses();
+            statuses.set_status(TypeId::of::<W>(), type_name::<W>(), WorkerStatus::Failed);
+            statuses.set_error(TypeId::of::<W>(), type_name::<W>(), e.to_string());
+            return Err(Error::WorkerStart(type_name::<W>(), worker::Error(Box::new(e))));
+        }
+    };
+
+    node.insert_worker(
+        worker,
+        Box::new(|node: &mut BeeNode<B>| {
+            Box::pin(async move {
+                debug!("Stopping worker `{}`...", type_name::<W>());
+
+                match node.take_worker::<W>() {
+                    Some(worker) => worker
+                        .stop(node)
+                        .instrument(info_span!("stop", worker = type_name::<W>()))
+                        .await
+                        .map_err(|e| Error::WorkerStop(type_name::<W>(), worker::Error(Box::new(e)))),
+                    None => Ok(()),
+                }
+            })
+        }),
+    );
+
+    Ok(())
+}
+
+fn start_configured_worker<'a, B, W>(node: &'a mut BeeNode<B>, config: &Config) -> LocalBoxFuture<'a, Result<(), Error>>
+where
+    B: StorageBackend,
+    W: ConfiguredWorker<BeeNode<B>>,
+{
+    let config = config.worker::<_, W>();
+
+    Box::pin(async move { start_worker::<B, W>(node, config?).await })
+}
+
+fn start_default_worker<'a, B, W>(node: &'a mut BeeNode<B>, _config: &Config) -> LocalBoxFuture<'a, Result<(), Error>>
+where
+    B: StorageBackend,
+    W: Worker<BeeNode<B>>,
+    W::Config: Default,
+{
+    Box::pin(start_worker::<B, W>(node, W::Config::default()))
+}
+
+fn reconfigure_worker<'a, B, W>(node: &'a mut BeeNode<B>, config: &Config) -> LocalBoxFuture<'a, Result<bool, Error>>
+where
+    B: StorageBackend,
+    W: ConfiguredWorker<BeeNode<B>>,
+    W::Config: Send,
+{
+    let config = config.worker::<_, W>();
+
+    Box::pin(async move {
+        let config = config?;
+        // The worker is taken out of the node, to be reconfigured with a mutable access to the node, and put back.
+        let mut worker = match node.take_worker::<W>() {
+            Some(worker) => worker,
+            None => return Ok(false),
+        };
+
+        let result = worker.reconfigure(node, config).await;
+        node.restore_worker(worker);
+
+        result.map_err(|e| Error::WorkerReconfigure(type_name::<W>(), worker::Error(Box::new(e))))
+    })
+}
@@ -0,0 +1,58 @@
+// Copyright 2020-2021 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+use serde::Deserialize;
+
+use std::time::Duration;
+
+/// Builder for a [`BeeNode`](crate::node::BeeNode) configuration.
+#[derive(Default, Deserialize)]
+pub struct BeeNodeConfigBuilder {
+    /// Time, in milliseconds, that each worker is given to stop before it is force-dropped.
+    stop_deadline_ms: Option<u64>,
+}
+
+impl BeeNodeConfigBuilder {
+    /// Creates a new builder for a node configuration.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the time that each worker is given to stop, including its tasks, before it is force-dropped.
+    pub fn stop_deadline(mut self, deadline: Duration) -> Self {
+        self.stop_deadline_ms.replace(deadline.as_millis() as u64);
+        self
+    }
+
+    /// Builds a node configuration.
+    pub fn finish(self) -> BeeNodeConfig {
+        BeeNodeConfig {
+            stop_deadline: self.stop_deadline_ms.map(Duration::from_millis),
+        }
+    }
+}
+
+impl From<BeeNodeConfigBuilder> for BeeNodeConfig {
+    fn from(builder: BeeNodeConfigBuilder) -> Self {
+        builder.finish()
+    }
+}
+
+/// Configuration of a [`BeeNode`](crate::node::BeeNode).
+#[derive(Clone, Default)]
+pub struct BeeNodeConfig {
+    /// Time that each worker is given to stop, or `None` to wait for as long as it takes.
+    pub(crate) stop_deadline: Option<Duration>,
+}
+
+impl BeeNodeConfig {
+    /// Creates a new builder for a node configuration.
+    pub fn build() -> BeeNodeConfigBuilder {
+        BeeNodeConfigBuilder::new()
+    }
+
+    /// Returns the time that each worker is given to stop, if any.
+    pub fn stop_deadline(&self) -> Option<Duration> {
+        self.stop_deadline
+    }
+}
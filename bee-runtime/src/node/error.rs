@@ -0,0 +1,48 @@
+// Copyright 2020-2021 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{config, graph, worker};
+
+use thiserror::Error;
+
+use std::any::TypeId;
+
+/// Errors that may occur while building, running or stopping a [`BeeNode`](crate::node::BeeNode).
+#[derive(Error, Debug)]
+#[non_exhaustive]
+pub enum Error {
+    /// The worker dependency graph is invalid.
+    #[error("Invalid worker dependency graph: {0}")]
+    Graph(#[from] graph::Error),
+    /// A worker failed to start.
+    #[error("Worker `{0}` failed to start: {1}")]
+    WorkerStart(&'static str, worker::Error),
+    /// A worker failed to stop.
+    #[error("Worker `{0}` failed to stop: {1}")]
+    WorkerStop(&'static str, worker::Error),
+    /// The configuration of the node or of a worker is invalid.
+    #[error("Invalid configuration: {0}")]
+    Config(#[from] config::Error),
+    /// A worker failed to apply a new configuration.
+    #[error("Worker `{0}` failed to reconfigure: {1}")]
+    WorkerReconfigure(&'static str, worker::Error),
+    /// A worker can't be added to the running node as it is already running.
+    #[error("Worker `{0}` is already running.")]
+    WorkerRunning(&'static str),
+    /// A worker can't be removed from the running node as it is not running.
+    #[error("Worker `{0}` is not running.")]
+    WorkerNotRunning(&'static str),
+    /// A worker can't be added to the running node as one of its dependencies is not running.
+    #[error("Worker `{0}` depends on worker `{1}`, that is not running.")]
+    WorkerDependency(&'static str, &'static str),
+    /// A worker can't be added to the running node as one of its dependencies was never started on the node, such
+    /// that only its type id is known.
+    #[error("Worker `{0}` depends on a worker that was never started: {1:?}.")]
+    UnknownWorkerDependency(&'static str, TypeId),
+    /// A worker can't be removed from the running node as another running worker depends on it.
+    #[error("Worker `{0}` can't be removed as worker `{1}` depends on it.")]
+    WorkerRequired(&'static str, &'static str),
+    /// A worker can't be restarted along with a worker it depends on, as it wasn't started with a known configuration.
+    #[error("Worker `{0}` depends on a restarted worker, but can't be restarted itself.")]
+    WorkerNotRestartable(&'static str),
+}
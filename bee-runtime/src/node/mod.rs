@@ -1,16 +1,37 @@
 // Copyright 2020-2021 IOTA Stiftung
 // SPDX-License-Identifier: Apache-2.0
 
-//! Traits used to represent bee nodes and allow for their instantiation.
-
-use crate::{event::Bus, resource::ResourceHandle, worker::Worker};
+//! Traits used to represent bee nodes and allow for their instantiation, along with a reference implementation of
+//! these traits.
+
+mod bee;
+mod builder;
+mod config;
+mod error;
+mod report;
+
+pub use bee::BeeNode;
+pub use builder::BeeNodeBuilder;
+pub use config::{BeeNodeConfig, BeeNodeConfigBuilder};
+pub use error::Error;
+pub use report::{ReloadReport, StopOverrun, StopReport};
+
+use crate::{
+    cancellation::CancellationToken,
+    event::{AsyncBus, Bus},
+    executor::{Executor, TokioExecutor},
+    resource::ResourceHandle,
+    status::StatusRegistry,
+    supervisor::{self, RestartPolicy},
+    worker::{self, Worker},
+};
 
 use bee_storage::backend::StorageBackend;
 
 use async_trait::async_trait;
 use futures::{channel::oneshot, future::Future};
 
-use std::any::Any;
+use std::{any::Any, sync::Arc};
 
 /// A trait representing a node framework through which node workers may communicate.
 #[async_trait]
@@ -34,6 +55,48 @@ pub trait Node: Send + Sized + 'static {
         G: FnOnce(oneshot::Receiver<()>) -> F,
         F: Future<Output = ()> + Send + 'static;
 
+    /// Spawn a new supervised node task associated with the given worker.
+    ///
+    /// The task is created by calling `g` and, when it fails or ends before being shut down, it may be created again
+    /// according to the restart policy of the worker. Failures are reported on the event bus as
+    /// [`WorkerFailed`](crate::supervisor::WorkerFailed) events.
+    ///
+    /// The default implementation follows the default [`RestartPolicy`], that never restarts the task, and only reports
+    /// failures on the event bus.
+    fn spawn_supervised<W, G, F>(&mut self, g: G)
+    where
+        W: Worker<Self>,
+        G: FnMut(oneshot::Receiver<()>) -> F + Send + 'static,
+        F: Future<Output = Result<(), worker::Error>> + Send + 'static,
+    {
+        let executor = self.executor();
+        let bus = self.bus();
+
+        self.spawn::<W, _, _>(move |shutdown| {
+            supervisor::supervise::<W, _, _>(
+                RestartPolicy::default(),
+                executor,
+                bus,
+                StatusRegistry::default(),
+                shutdown,
+                g,
+            )
+        });
+    }
+
+    /// Get the executor that runs the tasks of the node, for workers to spawn detached tasks, wait or run blocking work.
+    ///
+    /// The default implementation returns a [`TokioExecutor`], running on the ambient tokio runtime.
+    fn executor(&self) -> Arc<dyn Executor> {
+        Arc::new(TokioExecutor)
+    }
+
+    /// Get the cancellation token of the node, that shuts down the tasks of every worker when cancelled.
+    ///
+    /// The token is cancelled once [`Node::stop`] has stopped every worker, such that detached tasks may end along with
+    /// the node. Cancelling it doesn't stop the workers themselves, which still happens through [`Node::stop`].
+    fn cancellation_token(&self) -> CancellationToken;
+
     /// Get a reference to the state of a worker.
     fn worker<W>(&self) -> Option<&W>
     where
@@ -60,6 +123,12 @@ pub trait Node: Send + Sized + 'static {
     fn bus(&self) -> ResourceHandle<Bus<'static>> {
         self.resource()
     }
+
+    /// Obtain an owning handle to the node's asynchronous event bus.
+    #[track_caller]
+    fn async_bus(&self) -> ResourceHandle<AsyncBus> {
+        self.resource()
+    }
 }
 
 /// A trait that provides generic build configuration capabilities for a node.
@@ -0,0 +1,79 @@
+// Copyright 2020-2021 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::resource::ResourceReport;
+
+use std::{fmt, time::Duration};
+
+/// A worker that did not stop before the stop deadline and had to be force-dropped.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct StopOverrun {
+    /// The name of the worker.
+    pub worker: &'static str,
+    /// The deadline that the worker exceeded.
+    pub deadline: Duration,
+    /// The number of tasks of the worker that were still running and had to be aborted.
+    pub aborted_tasks: usize,
+}
+
+impl fmt::Display for StopOverrun {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Worker `{}` did not stop within {:?}, {} task(s) aborted.",
+            self.worker, self.deadline, self.aborted_tasks
+        )
+    }
+}
+
+/// A report of the shutdown of a [`BeeNode`](crate::node::BeeNode).
+#[derive(Clone, Debug, Default)]
+pub struct StopReport {
+    /// The workers that were force-dropped because they exceeded the stop deadline, in stop order.
+    pub overruns: Vec<StopOverrun>,
+    /// The resources that were still in use once all workers were stopped, along with their live handles.
+    pub leaked_resources: Vec<ResourceReport>,
+}
+
+impl StopReport {
+    /// Whether the node was stopped without any issue worth reporting.
+    pub fn is_clean(&self) -> bool {
+        self.overruns.is_empty() && self.leaked_resources.is_empty()
+    }
+}
+
+impl fmt::Display for StopReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_clean() {
+            return write!(f, "Node stopped cleanly.");
+        }
+
+        write!(f, "Node stopped with issues:")?;
+        for overrun in self.overruns.iter() {
+            write!(f, "\n{}", overrun)?;
+        }
+        if !self.leaked_resources.is_empty() {
+            write!(f, "\nLeaked resources:")?;
+        }
+        for resource in self.leaked_resources.iter() {
+            write!(f, "\n{}", resource)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// A report of the application of a new configuration to a [`BeeNode`](crate::node::BeeNode).
+#[derive(Clone, Debug, Default)]
+pub struct ReloadReport {
+    /// The workers that applied their new configuration through `Worker::reconfigure`.
+    pub reconfigured: Vec<&'static str>,
+    /// The workers that were restarted with their new configuration, along with the workers depending on them, in
+    /// startup order.
+    pub restarted: Vec<&'static str>,
+    /// The workers that were force-dropped because they exceeded the stop deadline while being restarted.
+    pub overruns: Vec<StopOverrun>,
+    /// The resources of the restarted workers that were still in use once these workers were stopped, along with their
+    /// live handles.
+    pub leaked_resources: Vec<ResourceReport>,
+}
@@ -0,0 +1,146 @@
+// Copyright 2020-2021 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+//! A module that detects configuration reload requests.
+//!
+//! Reloads are requested through [`ReloadRequested`] events, dispatched on `SIGHUP` by the
+//! [`SignalWorker`](crate::signal::SignalWorker) or on configuration file changes by the [`ConfigWatcher`]. The owner of
+//! the node reacts to them:
+//! ```ignore
+//! let mut reloads = ReloadRequests::new(&node.bus());
+//!
+//! while reloads.next().await.is_some() {
+//!     node.reload(loader().load()?).await?;
+//! }
+//! ```
+
+use crate::{
+    event::{Bus, ListenerHandle},
+    node::Node,
+    schedule::{Interval, MissedTickPolicy},
+    shutdown_stream::ShutdownStream,
+    signal::ReloadRequested,
+    worker::Worker,
+};
+
+use async_trait::async_trait;
+use futures::{
+    channel::mpsc,
+    stream::FusedStream,
+    task::{Context, Poll},
+    Stream, StreamExt,
+};
+use log::{info, warn};
+
+use std::{
+    any::TypeId,
+    convert::Infallible,
+    path::{Path, PathBuf},
+    pin::Pin,
+    time::{Duration, SystemTime},
+};
+
+/// Configuration of a [`ConfigWatcher`].
+#[derive(Clone, Debug)]
+pub struct ConfigWatcherConfig {
+    /// The path of the watched configuration file.
+    pub path: PathBuf,
+    /// The time between two checks of the file.
+    pub interval: Duration,
+}
+
+impl ConfigWatcherConfig {
+    /// Creates a configuration that checks the given file every second.
+    pub fn new<P: AsRef<Path>>(path: P) -> Self {
+        Self {
+            path: path.as_ref().to_owned(),
+            interval: Duration::from_secs(1),
+        }
+    }
+
+    /// Sets the time between two checks of the file.
+    pub fn with_interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+}
+
+/// A worker that dispatches a [`ReloadRequested`] event on the [`Bus`] of the node whenever the modification time of a
+/// configuration file changes.
+pub struct ConfigWatcher;
+
+#[async_trait]
+impl<N: Node> Worker<N> for ConfigWatcher {
+    type Config = ConfigWatcherConfig;
+    type Error = Infallible;
+
+    async fn start(node: &mut N, config: Self::Config) -> Result<Self, Self::Error> {
+        let bus = node.bus();
+        let interval = Interval::new(node.executor(), config.interval, MissedTickPolicy::Skip);
+
+        node.spawn::<Self, _, _>(|shutdown| async move {
+            let mut modified = last_modified(&config.path);
+            let mut ticks = ShutdownStream::new(shutdown, interval);
+
+            while ticks.next().await.is_some() {
+                let current = last_modified(&config.path);
+
+                if current != modified {
+                    if current.is_none() {
+                        warn!(
+                            "Configuration file `{}` is not readable anymore.",
+                            config.path.display()
+                        );
+                    } else {
+                        info!("Configuration file `{}` changed.", config.path.display());
+                        bus.dispatch(ReloadRequested);
+                    }
+                    modified = current;
+                }
+            }
+        });
+
+        Ok(Self)
+    }
+}
+
+/// A stream of the [`ReloadRequested`] events dispatched on a [`Bus`].
+///
+/// Dropping the stream stops listening to the bus.
+pub struct ReloadRequests {
+    _listener: ListenerHandle<'static>,
+    receiver: mpsc::UnboundedReceiver<ReloadRequested>,
+}
+
+impl ReloadRequests {
+    /// Start listening to the reload requests dispatched on the given bus.
+    pub fn new(bus: &Bus<'static>) -> Self {
+        let (sender, receiver) = mpsc::unbounded();
+
+        Self {
+            _listener: bus.add_listener_raw(TypeId::of::<Self>(), move |event: &ReloadRequested| {
+                // The receiver is only gone once the listener has been removed.
+                let _ = sender.unbounded_send(*event);
+            }),
+            receiver,
+        }
+    }
+}
+
+impl Stream for ReloadRequests {
+    type Item = ReloadRequested;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<Option<Self::Item>> {
+        self.receiver.poll_next_unpin(cx)
+    }
+}
+
+impl FusedStream for ReloadRequests {
+    fn is_terminated(&self) -> bool {
+        self.receiver.is_terminated()
+    }
+}
+
+fn last_modified(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path).and_then(|metadata| metadata.modified()).ok()
+}
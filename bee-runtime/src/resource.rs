@@ -7,21 +7,55 @@ use log::warn;
 
 use std::{
     any::{type_name, Any},
+    cmp::Reverse,
     collections::HashMap,
+    fmt,
     ops::Deref,
     panic::Location,
     sync::{
         atomic::{AtomicUsize, Ordering},
         Arc, Mutex, Weak,
     },
+    time::{Duration, Instant},
 };
 
 static RESOURCE_ID: AtomicUsize = AtomicUsize::new(0);
 
+type Usages = Mutex<HashMap<usize, (&'static Location<'static>, Instant)>>;
+
+/// A live clone of a [`ResourceHandle`].
+#[derive(Clone, Debug)]
+pub struct HandleReport {
+    /// The location at which the handle was cloned or upgraded.
+    pub location: &'static Location<'static>,
+    /// The time elapsed since the handle was cloned or upgraded.
+    pub age: Duration,
+}
+
+/// A report of all the live clones of the [`ResourceHandle`]s of a resource.
+#[derive(Clone, Debug)]
+pub struct ResourceReport {
+    /// The name of the resource type.
+    pub type_name: &'static str,
+    /// The live handles to the resource, oldest first.
+    pub handles: Vec<HandleReport>,
+}
+
+impl fmt::Display for ResourceReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "`{}` has {} live handle(s)", self.type_name, self.handles.len())?;
+        for handle in self.handles.iter() {
+            write!(f, "\n- {} ({:.3}s old)", handle.location, handle.age.as_secs_f64())?;
+        }
+        Ok(())
+    }
+}
+
 /// An owning handle to a node resource.
 pub struct ResourceHandle<R> {
     id: Option<usize>,
-    inner: Arc<(R, Mutex<HashMap<usize, &'static Location<'static>>>)>,
+    // The usages are shared on their own, to be observed without ever holding a strong reference to the resource.
+    inner: Arc<(R, Arc<Usages>)>,
 }
 
 impl<R> ResourceHandle<R> {
@@ -29,15 +63,36 @@ impl<R> ResourceHandle<R> {
     pub fn new(res: R) -> Self {
         Self {
             id: None,
-            inner: Arc::new((res, Mutex::new(HashMap::new()))),
+            inner: Arc::new((res, Arc::new(Mutex::new(HashMap::new())))),
         }
     }
 
     /// Turn this owned resource handle into a weak non-owning handle.
     pub fn into_weak(self) -> WeakHandle<R> {
-        let inner = Arc::downgrade(&self.inner);
+        let weak = WeakHandle {
+            inner: Arc::downgrade(&self.inner),
+            usages: Arc::downgrade(&self.inner.1),
+        };
         drop(self);
-        WeakHandle { inner }
+        weak
+    }
+
+    /// Count the live clones of this handle for as long as the resource exists, without keeping it alive nor
+    /// preventing [`ResourceHandle::try_unwrap`] from succeeding.
+    pub(crate) fn handle_counter(&self) -> impl Fn() -> Option<usize> + Send + Sync {
+        let usages = Arc::downgrade(&self.inner.1);
+
+        move || usages.upgrade().map(|usages| usages.lock().unwrap().len())
+    }
+
+    /// Report every live clone of this handle, including this one if it is a clone.
+    pub fn report(&self) -> ResourceReport {
+        report::<R>(&self.inner.1)
+    }
+
+    /// Whether this handle is the only one left to the resource.
+    pub(crate) fn is_unique(&self) -> bool {
+        Arc::strong_count(&self.inner) == 1
     }
 
     /// Attempt to gain ownership over the resource, returning `None` if the resource is still in use.
@@ -45,26 +100,39 @@ impl<R> ResourceHandle<R> {
     where
         R: Any,
     {
+        self.try_take().ok()
+    }
+
+    /// Attempt to gain ownership over the resource, giving the handle back if the resource is still in use.
+    pub(crate) fn try_take(mut self) -> Result<R, Self>
+    where
+        R: Any,
+    {
+        // The usage of this handle doesn't show in the report, and is restored if the resource is still in use.
+        let usage = self
+            .id
+            .take()
+            .and_then(|id| Some((id, self.inner.1.lock().unwrap().remove(&id)?)));
         let inner = self.inner.clone();
         drop(self);
         match Arc::try_unwrap(inner) {
-            Ok((res, _)) => Some(res),
+            Ok((res, _)) => Ok(res),
             Err(inner) => {
-                let usages = inner
-                    .1
-                    .lock()
-                    .unwrap()
-                    .values()
-                    .fold(String::new(), |s, loc| format!("{}\n- {}", s, loc));
                 warn!(
                     "Attempted to gain ownership resource `{}` but it is still being used. This is not, by itself, a \
                     problem but may indicate that a node task or event listener is not being stopped at the \
                     appropriate time during the shutdown process. Using arcane magic, we determined that the resource \
                     is still being used in the following places: {}",
                     type_name::<R>(),
-                    usages,
+                    report::<R>(&inner.1),
                 );
-                None
+                if let Some((id, usage)) = usage {
+                    inner.1.lock().unwrap().insert(id, usage);
+                }
+                Err(Self {
+                    id: usage.map(|(id, _)| id),
+                    inner,
+                })
             }
         }
     }
@@ -74,7 +142,11 @@ impl<R> Clone for ResourceHandle<R> {
     #[track_caller]
     fn clone(&self) -> Self {
         let new_id = RESOURCE_ID.fetch_add(1, Ordering::Relaxed);
-        self.inner.1.lock().unwrap().insert(new_id, Location::caller());
+        self.inner
+            .1
+            .lock()
+            .unwrap()
+            .insert(new_id, (Location::caller(), Instant::now()));
         Self {
             id: Some(new_id),
             inner: self.inner.clone(),
@@ -100,7 +172,8 @@ impl<R> Drop for ResourceHandle<R> {
 
 /// An non-owning handle to a node resource.
 pub struct WeakHandle<R> {
-    inner: Weak<(R, Mutex<HashMap<usize, &'static Location<'static>>>)>,
+    inner: Weak<(R, Arc<Usages>)>,
+    usages: Weak<Usages>,
 }
 
 impl<R> WeakHandle<R> {
@@ -109,18 +182,47 @@ impl<R> WeakHandle<R> {
     pub fn upgrade(&self) -> Option<ResourceHandle<R>> {
         let new_id = RESOURCE_ID.fetch_add(1, Ordering::Relaxed);
         let inner = self.inner.upgrade()?;
-        inner.1.lock().unwrap().insert(new_id, Location::caller());
+        inner
+            .1
+            .lock()
+            .unwrap()
+            .insert(new_id, (Location::caller(), Instant::now()));
         Some(ResourceHandle {
             id: Some(new_id),
             inner,
         })
     }
+
+    /// Report every live clone of the handles to the resource, if it still exists, without creating a new one.
+    pub fn report(&self) -> Option<ResourceReport> {
+        self.usages.upgrade().map(|usages| report::<R>(&usages))
+    }
 }
 
 impl<R> Clone for WeakHandle<R> {
     fn clone(&self) -> Self {
         Self {
             inner: self.inner.clone(),
+            usages: self.usages.clone(),
         }
     }
 }
+
+fn report<R>(usages: &Usages) -> ResourceReport {
+    let mut handles = usages
+        .lock()
+        .unwrap()
+        .values()
+        .map(|(location, created)| HandleReport {
+            location,
+            age: created.elapsed(),
+        })
+        .collect::<Vec<_>>();
+
+    handles.sort_by_key(|handle| Reverse(handle.age));
+
+    ResourceReport {
+        type_name: type_name::<R>(),
+        handles,
+    }
+}
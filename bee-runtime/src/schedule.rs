@@ -0,0 +1,374 @@
+// Copyright 2020-2021 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+//! A module that provides periodic and scheduled tasks for workers.
+//!
+//! Tasks are spawned through [`Node::spawn`] and stop being scheduled as soon as the shutdown signal of their worker is
+//! triggered, which replaces the usual timer wrapped in a `ShutdownStream`:
+//! ```ignore
+//! schedule::spawn_interval::<MyWorker, _, _, _>(node, Duration::from_secs(10), MissedTickPolicy::Skip, || async {
+//!     /* actual logic */
+//! });
+//! ```
+
+use crate::{
+    executor::{Executor, ExecutorExt},
+    node::Node,
+    shutdown_future::ShutdownFuture,
+    shutdown_stream::ShutdownStream,
+    worker::Worker,
+};
+
+use futures::{
+    future::{self, BoxFuture, Either, Future},
+    ready,
+    task::{Context, Poll},
+    Stream, StreamExt,
+};
+use thiserror::Error;
+use tokio::time::MissedTickBehavior;
+
+use std::{
+    pin::Pin,
+    str::FromStr,
+    sync::Arc,
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+/// What happens to the ticks that were missed because a task took longer than the time between two ticks.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MissedTickPolicy {
+    /// Missed ticks are skipped, the next tick happens at the next scheduled time.
+    Skip,
+    /// Missed ticks happen immediately, back to back, until the schedule has caught up.
+    Burst,
+    /// The schedule is shifted, the next tick happens one period after the late one.
+    ///
+    /// Cron schedules being tied to the calendar, this behaves like `Skip` for them.
+    Delay,
+}
+
+impl From<MissedTickPolicy> for MissedTickBehavior {
+    fn from(policy: MissedTickPolicy) -> Self {
+        match policy {
+            MissedTickPolicy::Skip => MissedTickBehavior::Skip,
+            MissedTickPolicy::Burst => MissedTickBehavior::Burst,
+            MissedTickPolicy::Delay => MissedTickBehavior::Delay,
+        }
+    }
+}
+
+/// A stream of ticks, one every `period` on the clock of an [`Executor`], starting immediately.
+///
+/// Each tick yields the time it was scheduled at.
+pub struct Interval {
+    executor: Arc<dyn Executor>,
+    period: Duration,
+    missed: MissedTickPolicy,
+    next: Instant,
+    sleep: Option<BoxFuture<'static, ()>>,
+}
+
+impl Interval {
+    /// Create an interval that ticks every `period`, handling missed ticks according to `missed`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `period` is zero.
+    pub fn new(executor: Arc<dyn Executor>, period: Duration, missed: MissedTickPolicy) -> Self {
+        assert!(period > Duration::ZERO, "the period of an interval must be non-zero");
+
+        Self {
+            next: executor.now(),
+            executor,
+            period,
+            missed,
+            sleep: None,
+        }
+    }
+}
+
+impl Stream for Interval {
+    type Item = Instant;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+
+        loop {
+            let now = this.executor.now();
+
+            if now >= this.next {
+                let tick = this.next;
+                let late = now - tick;
+
+                this.next = match this.missed {
+                    MissedTickPolicy::Burst => tick + this.period,
+                    MissedTickPolicy::Delay => now + this.period,
+                    // The first scheduled time after now, computed from the remainder of the lateness not to overflow
+                    // however many periods were missed.
+                    MissedTickPolicy::Skip => {
+                        let elapsed = late.as_nanos() % this.period.as_nanos();
+                        now + (this.period - Duration::from_nanos(elapsed as u64))
+                    }
+                };
+                this.sleep = None;
+
+                return Poll::Ready(Some(tick));
+            }
+
+            let (executor, deadline) = (&this.executor, this.next);
+
+            ready!(this
+                .sleep
+                .get_or_insert_with(|| executor.sleep_until(deadline))
+                .as_mut()
+                .poll(cx));
+            this.sleep = None;
+        }
+    }
+}
+
+/// Errors that may occur when parsing a cron schedule.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum Error {
+    /// The schedule doesn't have exactly five fields.
+    #[error("Cron schedule `{0}` must have 5 fields: minute, hour, day of month, month and day of week.")]
+    FieldCount(String),
+    /// A field of the schedule is invalid.
+    #[error("Invalid cron field `{0}`, expected values between {1} and {2}.")]
+    InvalidField(String, u32, u32),
+}
+
+/// A cron-like schedule, in UTC.
+///
+/// Schedules are made of five fields, `minute hour day-of-month month day-of-week`, each of them being `*` or a
+/// comma-separated list of values or ranges, with an optional step, e.g. `*/15 8-18 * * 1-5`. Sunday is both `0` and
+/// `7` and, like cron, a day matches if either its day of the month or its day of the week does when both are
+/// restricted, i.e. don't start with `*`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CronSchedule {
+    minutes: u64,
+    hours: u64,
+    days: u64,
+    months: u64,
+    weekdays: u64,
+    any_day: bool,
+    any_weekday: bool,
+}
+
+impl FromStr for CronSchedule {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let fields = s.split_whitespace().collect::<Vec<_>>();
+
+        if fields.len() != 5 {
+            return Err(Error::FieldCount(s.to_owned()));
+        }
+
+        let mut weekdays = parse_field(fields[4], 0, 7)?;
+        // Sunday is both 0 and 7.
+        if weekdays & (1 << 7) != 0 {
+            weekdays |= 1;
+        }
+
+        Ok(Self {
+            minutes: parse_field(fields[0], 0, 59)?,
+            hours: parse_field(fields[1], 0, 23)?,
+            days: parse_field(fields[2], 1, 31)?,
+            months: parse_field(fields[3], 1, 12)?,
+            weekdays,
+            // Like cron, a field is only considered restricted if it doesn't start with `*`, e.g. `*/2` isn't.
+            any_day: fields[2].starts_with('*'),
+            any_weekday: fields[4].starts_with('*'),
+        })
+    }
+}
+
+impl CronSchedule {
+    /// Compute the first time strictly after `time` that matches the schedule, if there is one within the next five
+    /// years.
+    pub fn next_after(&self, time: SystemTime) -> Option<SystemTime> {
+        const MINUTES_PER_DAY: u64 = 24 * 60;
+        const MAX_DAYS: u64 = 5 * 366;
+
+        let start = time.duration_since(UNIX_EPOCH).unwrap_or_default().as_secs() / 60 + 1;
+        let mut minutes = start;
+
+        while minutes - start < MAX_DAYS * MINUTES_PER_DAY {
+            let days = minutes / MINUTES_PER_DAY;
+            let (_, month, day) = civil_from_days(days);
+            // The Unix epoch was a Thursday.
+            let weekday = (days + 4) % 7;
+
+            if !self.matches_day(month, day, weekday) {
+                minutes = (days + 1) * MINUTES_PER_DAY;
+                continue;
+            }
+
+            let hour = (minutes % MINUTES_PER_DAY) / 60;
+            if self.hours & (1 << hour) == 0 {
+                minutes = (minutes / 60 + 1) * 60;
+                continue;
+            }
+
+            if self.minutes & (1 << (minutes % 60)) == 0 {
+                minutes += 1;
+                continue;
+            }
+
+            return Some(UNIX_EPOCH + Duration::from_secs(minutes * 60));
+        }
+
+        None
+    }
+
+    fn matches_day(&self, month: u64, day: u64, weekday: u64) -> bool {
+        if self.months & (1 << month) == 0 {
+            return false;
+        }
+
+        let day_matches = self.days & (1 << day) != 0;
+        let weekday_matches = self.weekdays & (1 << weekday) != 0;
+
+        match (self.any_day, self.any_weekday) {
+            (false, false) => day_matches || weekday_matches,
+            _ => day_matches && weekday_matches,
+        }
+    }
+}
+
+fn parse_field(field: &str, min: u32, max: u32) -> Result<u64, Error> {
+    let invalid = || Error::InvalidField(field.to_owned(), min, max);
+    let parse = |value: &str| {
+        value
+            .parse::<u32>()
+            .ok()
+            .filter(|value| (min..=max).contains(value))
+            .ok_or_else(invalid)
+    };
+    let mut mask = 0u64;
+
+    for item in field.split(',') {
+        let (range, step) = match item.split_once('/') {
+            Some((range, step)) => (
+                range,
+                step.parse::<u32>().ok().filter(|step| *step > 0).ok_or_else(invalid)?,
+            ),
+            None => (item, 1),
+        };
+        let (first, last) = match range {
+            "*" => (min, max),
+            range => match range.split_once('-') {
+                Some((first, last)) => (parse(first)?, parse(last)?),
+                None => {
+                    let value = parse(range)?;
+                    // A single value with a step, e.g. `5/10`, runs until the end of the range, like cron.
+                    (value, if item.contains('/') { max } else { value })
+                }
+            },
+        };
+
+        if first > last {
+            return Err(invalid());
+        }
+
+        for value in (first..=last).step_by(step as usize) {
+            mask |= 1 << value;
+        }
+    }
+
+    Ok(mask)
+}
+
+// Converts a number of days since the Unix epoch into a (year, month, day) date of the proleptic Gregorian calendar.
+fn civil_from_days(days: u64) -> (u64, u64, u64) {
+    let z = days + 719_468;
+    let era = z / 146_097;
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+
+    (year, month, day)
+}
+
+/// Spawn a task of the worker `W` that runs `f` every `period`, starting immediately, until the worker is shut down.
+pub fn spawn_interval<W, N, F, Fut>(node: &mut N, period: Duration, missed: MissedTickPolicy, mut f: F)
+where
+    W: Worker<N>,
+    N: Node,
+    F: FnMut() -> Fut + Send + 'static,
+    Fut: Future<Output = ()> + Send,
+{
+    let interval = Interval::new(node.executor(), period, missed);
+
+    node.spawn::<W, _, _>(|shutdown| async move {
+        let mut ticks = ShutdownStream::new(shutdown, interval);
+
+        while ticks.next().await.is_some() {
+            f().await;
+        }
+    });
+}
+
+/// Spawn a task of the worker `W` that runs `f` at every time matching the cron `schedule`, until the worker is shut
+/// down.
+///
+/// The wall-clock time is read once, when spawning the task, and then follows the clock of the executor of the node.
+pub fn spawn_cron<W, N, F, Fut>(node: &mut N, schedule: CronSchedule, missed: MissedTickPolicy, mut f: F)
+where
+    W: Worker<N>,
+    N: Node,
+    F: FnMut() -> Fut + Send + 'static,
+    Fut: Future<Output = ()> + Send,
+{
+    let executor = node.executor();
+    let (origin, start) = (SystemTime::now(), executor.now());
+    let now = {
+        let executor = executor.clone();
+        move || origin + executor.now().saturating_duration_since(start)
+    };
+
+    node.spawn::<W, _, _>(|mut shutdown| async move {
+        let mut scheduled = now();
+
+        loop {
+            let from = match missed {
+                MissedTickPolicy::Burst => scheduled,
+                MissedTickPolicy::Skip | MissedTickPolicy::Delay => scheduled.max(now()),
+            };
+            scheduled = match schedule.next_after(from) {
+                Some(next) => next,
+                None => break,
+            };
+
+            let sleep = executor.sleep(scheduled.duration_since(now()).unwrap_or_default());
+
+            if let Either::Left(_) = future::select(&mut shutdown, sleep).await {
+                break;
+            }
+            f().await;
+        }
+    });
+}
+
+/// Spawn a task of the worker `W` that runs `f` once, after `delay`, unless the worker is shut down before.
+pub fn spawn_delayed<W, N, F, Fut>(node: &mut N, delay: Duration, f: F)
+where
+    W: Worker<N>,
+    N: Node,
+    F: FnOnce() -> Fut + Send + 'static,
+    Fut: Future<Output = ()> + Send,
+{
+    let sleep = node.executor().sleep(delay);
+
+    node.spawn::<W, _, _>(|shutdown| async move {
+        if ShutdownFuture::new(shutdown, sleep).await.is_some() {
+            f().await;
+        }
+    });
+}
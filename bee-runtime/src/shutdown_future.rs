@@ -0,0 +1,79 @@
+// Copyright 2020-2021 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+//! A module to simplify selecting between a shutdown signal and a future.
+//!
+//! The `ShutdownFuture` type is the counterpart of `ShutdownStream` for futures, it can be used to replace this
+//! pattern:
+//! ```ignore
+//! select! {
+//!     _ = shutdown => None,
+//!     output = future => Some(output),
+//! }
+//! ```
+//! by this one:
+//! ```ignore
+//! ShutdownFuture::new(shutdown, future).await
+//! ```
+
+use futures::{
+    channel::oneshot,
+    future::{self, FusedFuture, Future},
+    task::{Context, Poll},
+    FutureExt,
+};
+
+use std::{marker::Unpin, pin::Pin};
+
+type Shutdown = oneshot::Receiver<()>;
+type FusedShutdown = future::Fuse<Shutdown>;
+
+/// A future with a shutdown.
+///
+/// This type wraps a shutdown receiver and a future to produce a new future that resolves to `None` when the shutdown
+/// receiver is triggered first, or to the output of the future otherwise.
+pub struct ShutdownFuture<F> {
+    shutdown: FusedShutdown,
+    future: future::Fuse<F>,
+}
+
+impl<F: Future> ShutdownFuture<F> {
+    /// Create a new `ShutdownFuture` from a shutdown receiver and a future.
+    pub fn new(shutdown: Shutdown, future: F) -> Self {
+        Self {
+            shutdown: shutdown.fuse(),
+            future: future.fuse(),
+        }
+    }
+
+    /// Consume and split the `ShutdownFuture` into its shutdown receiver and future.
+    pub fn split(self) -> (FusedShutdown, future::Fuse<F>) {
+        (self.shutdown, self.future)
+    }
+}
+
+impl<F: Future + Unpin> Future for ShutdownFuture<F> {
+    type Output = Option<F::Output>;
+
+    /// The shutdown receiver is polled first, if it is not ready, the future is polled. This guarantees that checking
+    /// for shutdown always happens first.
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<Self::Output> {
+        if !self.shutdown.is_terminated() {
+            if self.shutdown.poll_unpin(cx).is_ready() {
+                return Poll::Ready(None);
+            }
+
+            if !self.future.is_terminated() {
+                return self.future.poll_unpin(cx).map(Some);
+            }
+        }
+
+        Poll::Ready(None)
+    }
+}
+
+impl<F: Future + Unpin> FusedFuture for ShutdownFuture<F> {
+    fn is_terminated(&self) -> bool {
+        self.shutdown.is_terminated() || self.future.is_terminated()
+    }
+}
@@ -0,0 +1,150 @@
+// Copyright 2020-2021 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+//! A module that turns operating system signals into node shutdown and reload requests.
+//!
+//! Registering the [`SignalWorker`] replaces the usual hand-written signal handling of node binaries:
+//! ```ignore
+//! let (stop_sender, stop_receiver) = oneshot::channel();
+//!
+//! let node = builder
+//!     .with_worker_cfg::<SignalWorker>(SignalWorkerConfig::new(stop_sender))
+//!     .finish()
+//!     .await?;
+//!
+//! // Wait for SIGINT or SIGTERM.
+//! let _ = stop_receiver.await;
+//! node.stop().await?;
+//! ```
+
+use crate::{executor::ExecutorExt, node::Node, shutdown_stream::ShutdownStream, worker::Worker};
+
+use async_trait::async_trait;
+use futures::{
+    channel::oneshot,
+    stream::{self, BoxStream},
+    Stream, StreamExt,
+};
+use log::{info, warn};
+
+use std::{io, process};
+
+/// The event dispatched on the [`Bus`](crate::event::Bus) of the node when a reload is requested, i.e. on `SIGHUP`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ReloadRequested;
+
+/// A signal handled by the [`SignalWorker`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Signal {
+    /// A request to stop the node, i.e. `SIGINT` or `SIGTERM`.
+    Stop,
+    /// A request to reload the configuration of the node, i.e. `SIGHUP`.
+    Reload,
+}
+
+/// Configuration of a [`SignalWorker`].
+pub struct SignalWorkerConfig {
+    stop: oneshot::Sender<()>,
+    signals: Option<BoxStream<'static, Signal>>,
+}
+
+impl SignalWorkerConfig {
+    /// Creates a configuration that triggers the given shutdown channel on the first operating system stop signal.
+    pub fn new(stop: oneshot::Sender<()>) -> Self {
+        Self { stop, signals: None }
+    }
+
+    /// Handles the given stream of signals instead of the operating system signals, e.g. to simulate them in tests.
+    pub fn with_signals<S: Stream<Item = Signal> + Send + 'static>(mut self, signals: S) -> Self {
+        self.signals = Some(signals.boxed());
+        self
+    }
+}
+
+/// A worker that listens for operating system signals on behalf of the node.
+///
+/// - The first `SIGINT` or `SIGTERM` (`Ctrl-C` on non-Unix platforms) triggers the shutdown channel given in the
+///   configuration of the worker, whose receiver is expected to start the ordered [`Node::stop`].
+/// - Any further `SIGINT` or `SIGTERM` exits the process immediately, without waiting for the node to stop. Once the
+///   node is stopped, further signals are no longer handled.
+/// - `SIGHUP` dispatches a [`ReloadRequested`] event on the [`Bus`](crate::event::Bus) of the node.
+pub struct SignalWorker;
+
+#[async_trait]
+impl<N: Node> Worker<N> for SignalWorker {
+    type Config = SignalWorkerConfig;
+    type Error = io::Error;
+
+    async fn start(node: &mut N, config: Self::Config) -> Result<Self, Self::Error> {
+        let bus = node.bus();
+        let signals = match config.signals {
+            Some(signals) => signals,
+            None => signals()?,
+        };
+        let stop = config.stop;
+        let executor = node.executor();
+        let stopped = node.cancellation_token();
+
+        node.spawn::<Self, _, _>(|shutdown| async move {
+            let mut signals = ShutdownStream::new(shutdown, signals);
+
+            while let Some(signal) = signals.next().await {
+                match signal {
+                    Signal::Reload => {
+                        info!("Reload signal received.");
+                        bus.dispatch(ReloadRequested);
+                    }
+                    Signal::Stop => {
+                        info!("Stop signal received, stopping the node...");
+                        // The receiver may have been dropped, in which case nobody is interested in stopping the node.
+                        let _ = stop.send(());
+
+                        // Stopping the node also stops this worker, so a detached task handles any further signal
+                        // until the node is stopped.
+                        let (_, signals) = signals.split();
+                        let mut signals = ShutdownStream::new(stopped.signal(), signals);
+                        executor.spawn(async move {
+                            while let Some(signal) = signals.next().await {
+                                if signal == Signal::Stop {
+                                    warn!("Stop signal received again, exiting immediately.");
+                                    process::exit(1);
+                                }
+                            }
+                        });
+
+                        break;
+                    }
+                }
+            }
+        });
+
+        Ok(Self)
+    }
+}
+
+#[cfg(unix)]
+fn signals() -> io::Result<BoxStream<'static, Signal>> {
+    use tokio::signal::unix::{signal, SignalKind};
+
+    let stream = |kind, event| {
+        let mut signal = signal(kind)?;
+        Ok::<_, io::Error>(stream::poll_fn(move |cx| signal.poll_recv(cx)).map(move |_| event))
+    };
+
+    Ok(stream::select(
+        stream::select(
+            stream(SignalKind::interrupt(), Signal::Stop)?,
+            stream(SignalKind::terminate(), Signal::Stop)?,
+        ),
+        stream(SignalKind::hangup(), Signal::Reload)?,
+    )
+    .boxed())
+}
+
+#[cfg(not(unix))]
+fn signals() -> io::Result<BoxStream<'static, Signal>> {
+    Ok(stream::unfold((), |_| async {
+        tokio::signal::ctrl_c().await.ok().map(|_| (Signal::Stop, ()))
+    })
+    .boxed())
+}
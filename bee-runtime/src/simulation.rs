@@ -0,0 +1,97 @@
+// Copyright 2020-2021 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+//! A module that perturbs the scheduling of node tasks, deterministically, to explore their interleavings.
+//!
+//! While a simulation is entered on a thread, every task spawned by a node and polled on that thread randomly yields
+//! back to the runtime before being polled, according to a random number generator seeded by the simulation. On a
+//! single-threaded runtime, the order in which tasks run, and hence their interleaving, only depends on the seed. Tasks
+//! polled on other threads are not perturbed.
+//!
+//! This module is only available with the `simulation` feature, see the `bee-test` crate for a ready-made simulation
+//! runtime with virtual time.
+
+use futures::{
+    future::Future,
+    task::{Context, Poll},
+};
+
+use std::{cell::RefCell, pin::Pin};
+
+thread_local! {
+    static SCHEDULER: RefCell<Option<Scheduler>> = const { RefCell::new(None) };
+}
+
+// A SplitMix64 generator, good enough to pick interleavings and stable across versions.
+struct Scheduler {
+    state: u64,
+}
+
+impl Scheduler {
+    fn next(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+}
+
+/// A guard that leaves the simulation entered with [`enter`] when dropped.
+#[must_use = "the simulation is left as soon as the guard is dropped"]
+pub struct SimulationGuard {
+    previous: Option<Scheduler>,
+}
+
+impl Drop for SimulationGuard {
+    fn drop(&mut self) {
+        let previous = self.previous.take();
+        SCHEDULER.with(|scheduler| *scheduler.borrow_mut() = previous);
+    }
+}
+
+/// Enter a simulation with the given seed on the current thread, until the returned guard is dropped.
+pub fn enter(seed: u64) -> SimulationGuard {
+    SimulationGuard {
+        previous: SCHEDULER.with(|scheduler| scheduler.borrow_mut().replace(Scheduler { state: seed })),
+    }
+}
+
+/// Whether a simulation is entered on the current thread.
+pub fn is_entered() -> bool {
+    SCHEDULER.with(|scheduler| scheduler.borrow().is_some())
+}
+
+// Randomly decide whether the task being polled should yield, one time out of four.
+fn should_yield() -> bool {
+    SCHEDULER.with(|scheduler| match scheduler.borrow_mut().as_mut() {
+        Some(scheduler) => scheduler.next() % 4 == 0,
+        None => false,
+    })
+}
+
+/// A future that randomly yields before being polled while a simulation is entered.
+pub(crate) struct Perturbed<F> {
+    future: Pin<Box<F>>,
+}
+
+impl<F> Perturbed<F> {
+    pub(crate) fn new(future: F) -> Self {
+        Self {
+            future: Box::pin(future),
+        }
+    }
+}
+
+impl<F: Future> Future for Perturbed<F> {
+    type Output = F::Output;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        if should_yield() {
+            cx.waker().wake_by_ref();
+            return Poll::Pending;
+        }
+
+        self.future.as_mut().poll(cx)
+    }
+}
@@ -0,0 +1,159 @@
+// Copyright 2020-2021 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+//! A module that tracks the lifecycle and health of node workers.
+
+use std::{
+    any::TypeId,
+    fmt,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+/// The lifecycle status of a worker.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum WorkerStatus {
+    /// The worker is being started.
+    Starting,
+    /// The worker has been started.
+    Running,
+    /// The worker is being stopped.
+    Stopping,
+    /// The worker failed to start or to stop, or one of its tasks failed for good.
+    Failed,
+    /// The worker has been stopped.
+    Stopped,
+}
+
+impl fmt::Display for WorkerStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let status = match self {
+            Self::Starting => "starting",
+            Self::Running => "running",
+            Self::Stopping => "stopping",
+            Self::Failed => "failed",
+            Self::Stopped => "stopped",
+        };
+
+        write!(f, "{}", status)
+    }
+}
+
+/// The health of a worker, as reported by [`Worker::health`](crate::worker::Worker::health).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Health {
+    /// The worker is working as expected.
+    Healthy,
+    /// The worker is working, but not as expected, for the given reason.
+    Degraded(String),
+    /// The worker is not working, for the given reason.
+    Unhealthy(String),
+}
+
+/// A snapshot of the status of a worker.
+#[derive(Clone, Debug)]
+pub struct WorkerSnapshot {
+    /// The name of the worker.
+    pub name: &'static str,
+    /// The lifecycle status of the worker.
+    pub status: WorkerStatus,
+    /// The time elapsed since the worker was started, if it is running.
+    pub uptime: Option<Duration>,
+    /// The last error reported by the worker or one of its tasks, if any.
+    pub last_error: Option<String>,
+    /// The health reported by the worker, if it is running.
+    pub health: Option<Health>,
+}
+
+type HealthCheck = Box<dyn Fn() -> Option<Health> + Send + Sync>;
+
+struct Entry {
+    id: TypeId,
+    name: &'static str,
+    status: WorkerStatus,
+    started: Option<Instant>,
+    last_error: Option<String>,
+    health_check: Option<HealthCheck>,
+}
+
+/// A registry of the status of all the workers of a node.
+///
+/// [`BeeNode`](crate::node::BeeNode)s register one as a resource and keep it up to date, such that other workers or an
+/// admin endpoint can read it.
+#[derive(Clone, Default)]
+pub struct StatusRegistry {
+    entries: Arc<Mutex<Vec<Entry>>>,
+}
+
+impl StatusRegistry {
+    /// Take a snapshot of the status of all the workers, in startup order, running their health checks.
+    pub fn snapshot(&self) -> Vec<WorkerSnapshot> {
+        self.entries
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|entry| WorkerSnapshot {
+                name: entry.name,
+                status: entry.status,
+                uptime: entry.started.map(|started| started.elapsed()),
+                last_error: entry.last_error.clone(),
+                health: entry.health_check.as_ref().and_then(|check| check()),
+            })
+            .collect()
+    }
+
+    /// Get a snapshot of the status of the worker with the given name, if it is known.
+    pub fn worker(&self, name: &str) -> Option<WorkerSnapshot> {
+        self.snapshot().into_iter().find(|snapshot| snapshot.name == name)
+    }
+
+    /// Get the name of the worker with the given id, if it is known.
+    pub(crate) fn name(&self, id: TypeId) -> Option<&'static str> {
+        self.entries
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|entry| entry.id == id)
+            .map(|entry| entry.name)
+    }
+
+    fn update(&self, id: TypeId, name: &'static str, f: impl FnOnce(&mut Entry)) {
+        let mut entries = self.entries.lock().unwrap();
+
+        match entries.iter_mut().find(|entry| entry.id == id) {
+            Some(entry) => f(entry),
+            None => {
+                let mut entry = Entry {
+                    id,
+                    name,
+                    status: WorkerStatus::Starting,
+                    started: None,
+                    last_error: None,
+                    health_check: None,
+                };
+                f(&mut entry);
+                entries.push(entry);
+            }
+        }
+    }
+
+    pub(crate) fn set_status(&self, id: TypeId, name: &'static str, status: WorkerStatus) {
+        self.update(id, name, |entry| {
+            entry.status = status;
+            entry.started = match status {
+                WorkerStatus::Running => Some(Instant::now()),
+                WorkerStatus::Stopping => entry.started,
+                _ => None,
+            };
+        });
+    }
+
+    pub(crate) fn set_error(&self, id: TypeId, name: &'static str, error: String) {
+        self.update(id, name, |entry| entry.last_error = Some(error));
+    }
+
+    pub(crate) fn set_health_check(&self, id: TypeId, name: &'static str, health_check: Option<HealthCheck>) {
+        // Removing a health check under the lock guarantees that it is not running anymore.
+        self.update(id, name, |entry| entry.health_check = health_check);
+    }
+}
@@ -0,0 +1,137 @@
+// Copyright 2020-2021 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+//! A module that manages the lifecycle of the storage backend of a node.
+//!
+//! The [`StorageWorker`] starts the backend, which is then available to other workers through [`Node::storage`], and
+//! shuts it down when it is stopped, or once the last handle to it is dropped if it is still in use by then and that
+//! handle is dropped within a minute. Workers using the storage should list it in their dependencies, such that the
+//! backend is started before and shut down after them:
+//! ```ignore
+//! fn dependencies() -> &'static [TypeId] {
+//!     Box::leak(Box::from(vec![TypeId::of::<StorageWorker<Backend>>()]))
+//! }
+//! ```
+
+use crate::{
+    config::ConfiguredWorker,
+    executor::ExecutorExt,
+    node::Node,
+    resource::{ResourceReport, WeakHandle},
+    worker::Worker,
+};
+
+use bee_storage::backend::StorageBackend;
+
+use async_trait::async_trait;
+use log::{error, info};
+use thiserror::Error;
+
+use std::{any::type_name, time::Duration};
+
+/// The period at which a backend still in use when its worker stopped is checked for its last handle to be dropped.
+const SHUTDOWN_RETRY_PERIOD: Duration = Duration::from_millis(100);
+/// How long a backend still in use when its worker stopped is waited for, before giving up on shutting it down.
+const SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Errors that may occur while starting or shutting down a storage backend.
+#[derive(Error, Debug)]
+pub enum Error<E> {
+    /// The backend failed to start.
+    #[error("Starting storage backend failed: {0}.")]
+    Start(E),
+    /// The backend failed to shut down.
+    #[error("Shutting down storage backend failed: {0}.")]
+    Shutdown(E),
+    /// The backend could not be shut down yet because handles to it are still alive.
+    #[error("Storage backend can't be shut down while still in use: {0}")]
+    InUse(ResourceReport),
+}
+
+/// A worker that starts the storage backend `B`, registers it as a node resource and shuts it down when stopped.
+///
+/// Its configuration is the `storage` section of the node configuration, read with the `ConfigBuilder` of the backend.
+pub struct StorageWorker<B> {
+    backend: WeakHandle<B>,
+}
+
+#[async_trait]
+impl<B, N> Worker<N> for StorageWorker<B>
+where
+    B: StorageBackend,
+    B::Error: 'static,
+    N: Node<Backend = B>,
+{
+    type Config = B::Config;
+    type Error = Error<B::Error>;
+
+    async fn start(node: &mut N, config: Self::Config) -> Result<Self, Self::Error> {
+        let backend = B::start(config).await.map_err(Error::Start)?;
+
+        node.register_resource(backend);
+        info!("Storage backend `{}` started.", type_name::<B>());
+
+        Ok(Self {
+            backend: node.storage().into_weak(),
+        })
+    }
+
+    /// Shuts the backend down, or, if it is still in use, reports the handles to it and shuts it down once they are
+    /// all dropped, unless they are still alive after a minute, in which case the backend is never shut down.
+    async fn stop(self, node: &mut N) -> Result<(), Self::Error> {
+        if let Some(backend) = node.remove_resource::<B>() {
+            backend.shutdown().await.map_err(Error::Shutdown)?;
+            info!("Storage backend `{}` shut down.", type_name::<B>());
+            return Ok(());
+        }
+
+        // The backend was either removed by someone else, and can't be shut down here, or is still in use. The report
+        // is taken before upgrading, not to list the handle of this worker.
+        let (report, backend) = match (self.backend.report(), self.backend.upgrade()) {
+            (Some(report), Some(backend)) => (report, backend),
+            _ => return Ok(()),
+        };
+        let executor = node.executor();
+
+        executor.clone().spawn(async move {
+            let deadline = executor.now() + SHUTDOWN_TIMEOUT;
+            let mut backend = backend;
+
+            let backend = loop {
+                if backend.is_unique() {
+                    match backend.try_take() {
+                        Ok(backend) => break backend,
+                        Err(handle) => backend = handle,
+                    }
+                }
+                if executor.now() >= deadline {
+                    error!(
+                        "Storage backend `{}` still in use after {:?}, giving up on shutting it down.",
+                        type_name::<B>(),
+                        SHUTDOWN_TIMEOUT
+                    );
+                    return;
+                }
+                executor.sleep(SHUTDOWN_RETRY_PERIOD).await;
+            };
+
+            match backend.shutdown().await {
+                Ok(()) => info!("Storage backend `{}` shut down once unused.", type_name::<B>()),
+                Err(e) => error!("Shutting down storage backend `{}` failed: {}.", type_name::<B>(), e),
+            }
+        });
+
+        Err(Error::InUse(report))
+    }
+}
+
+impl<B, N> ConfiguredWorker<N> for StorageWorker<B>
+where
+    B: StorageBackend,
+    B::Error: 'static,
+    N: Node<Backend = B>,
+{
+    const SECTION: &'static str = "storage";
+
+    type ConfigBuilder = B::ConfigBuilder;
+}
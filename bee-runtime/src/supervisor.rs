@@ -0,0 +1,219 @@
+// Copyright 2020-2021 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+//! A module that supervises worker tasks and restarts them according to a [`RestartPolicy`].
+
+use crate::{
+    event::Bus,
+    executor::{Executor, ExecutorExt, JoinError, TaskHandle},
+    resource::ResourceHandle,
+    status::{StatusRegistry, WorkerStatus},
+    worker,
+};
+
+use futures::{
+    channel::oneshot,
+    future::{self, Either, Future},
+};
+use log::{debug, error};
+use thiserror::Error;
+use tracing::Instrument;
+
+use std::{
+    any::{type_name, TypeId},
+    sync::Arc,
+    time::Duration,
+};
+
+/// When a supervised task should be restarted.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Restart {
+    /// The task is never restarted.
+    Never,
+    /// The task is restarted if it fails, i.e. returns an error or panics.
+    OnFailure,
+    /// The task is restarted whenever it ends before being shut down, even successfully.
+    Always,
+}
+
+/// The restart policy of the supervised tasks of a worker.
+///
+/// Restarts are delayed by an exponential backoff, starting at `initial_backoff` and doubling with every restart up to
+/// `max_backoff`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RestartPolicy {
+    /// When tasks should be restarted.
+    pub restart: Restart,
+    /// The delay before the first restart.
+    pub initial_backoff: Duration,
+    /// The maximum delay between two restarts.
+    pub max_backoff: Duration,
+    /// The maximum number of restarts of a task, or `None` for no limit.
+    pub max_restarts: Option<usize>,
+}
+
+impl Default for RestartPolicy {
+    fn default() -> Self {
+        Self::never()
+    }
+}
+
+impl RestartPolicy {
+    fn new(restart: Restart) -> Self {
+        Self {
+            restart,
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_secs(30),
+            max_restarts: None,
+        }
+    }
+
+    /// A policy that never restarts tasks.
+    pub fn never() -> Self {
+        Self::new(Restart::Never)
+    }
+
+    /// A policy that restarts tasks when they fail.
+    pub fn on_failure() -> Self {
+        Self::new(Restart::OnFailure)
+    }
+
+    /// A policy that restarts tasks whenever they end before being shut down.
+    pub fn always() -> Self {
+        Self::new(Restart::Always)
+    }
+
+    /// Sets the initial and maximum delays between restarts.
+    pub fn with_backoff(mut self, initial: Duration, max: Duration) -> Self {
+        self.initial_backoff = initial;
+        self.max_backoff = max;
+        self
+    }
+
+    /// Sets the maximum number of restarts of a task.
+    pub fn with_max_restarts(mut self, max_restarts: usize) -> Self {
+        self.max_restarts.replace(max_restarts);
+        self
+    }
+
+    /// Compute the delay before the restart following `restarts` previous restarts, or `None` if the task should not be
+    /// restarted.
+    fn backoff(&self, failed: bool, restarts: usize) -> Option<Duration> {
+        let restart = match self.restart {
+            Restart::Never => false,
+            Restart::OnFailure => failed,
+            Restart::Always => true,
+        };
+
+        if !restart || matches!(self.max_restarts, Some(max) if restarts >= max) {
+            return None;
+        }
+
+        let factor = 1u32.checked_shl(restarts as u32).unwrap_or(u32::MAX);
+
+        Some(
+            self.initial_backoff
+                .checked_mul(factor)
+                .map_or(self.max_backoff, |backoff| backoff.min(self.max_backoff)),
+        )
+    }
+}
+
+/// The error reported when a supervised task panics.
+#[derive(Error, Debug)]
+#[error("Task panicked: {0}")]
+pub struct TaskPanicked(pub String);
+
+/// The event dispatched on the [`Bus`] of the node when a supervised task fails.
+#[derive(Debug)]
+pub struct WorkerFailed {
+    /// The name of the worker that owns the task.
+    pub worker: &'static str,
+    /// The error returned by the task, or a [`TaskPanicked`] error if the task panicked.
+    pub error: worker::Error,
+    /// The number of times the task had already been restarted.
+    pub restarts: usize,
+    /// The delay before the task is restarted, or `None` if it won't be.
+    pub backoff: Option<Duration>,
+}
+
+// Makes sure the current attempt doesn't outlive the supervisor if the latter is aborted.
+struct AbortOnDrop(TaskHandle<Result<(), worker::Error>>);
+
+impl Drop for AbortOnDrop {
+    fn drop(&mut self) {
+        self.0.abort();
+    }
+}
+
+/// Run the task of the worker `W` created by `g` until `shutdown` is triggered, restarting it according to `policy`.
+pub(crate) async fn supervise<W, G, F>(
+    policy: RestartPolicy,
+    executor: Arc<dyn Executor>,
+    bus: ResourceHandle<Bus<'static>>,
+    statuses: StatusRegistry,
+    mut shutdown: oneshot::Receiver<()>,
+    mut g: G,
+) where
+    W: 'static,
+    G: FnMut(oneshot::Receiver<()>) -> F,
+    F: Future<Output = Result<(), worker::Error>> + Send + 'static,
+{
+    let (id, name) = (TypeId::of::<W>(), type_name::<W>());
+    let mut restarts = 0;
+
+    loop {
+        let (task_shutdown_tx, task_shutdown_rx) = oneshot::channel();
+        let mut task = AbortOnDrop(executor.spawn(g(task_shutdown_rx).in_current_span()));
+
+        let result = match future::select(&mut shutdown, &mut task.0).await {
+            Either::Left(_) => {
+                // The task may have already ended by itself, in which case the receiver is gone.
+                let _ = task_shutdown_tx.send(());
+                if let Err(e) = (&mut task.0).await {
+                    error!("A task of worker `{}` did not end cleanly: {}.", name, e);
+                }
+                return;
+            }
+            Either::Right((result, _)) => result,
+        };
+
+        let error = match result {
+            Ok(Ok(())) => None,
+            Ok(Err(e)) => Some(e),
+            Err(JoinError::Panicked(message)) => Some(worker::Error(Box::new(TaskPanicked(message)))),
+            Err(e) => Some(worker::Error(Box::new(e))),
+        };
+        let backoff = policy.backoff(error.is_some(), restarts);
+
+        match error {
+            Some(error) => {
+                error!("A task of worker `{}` failed: {}.", name, error);
+                statuses.set_error(id, name, error.to_string());
+                if backoff.is_none() {
+                    statuses.set_status(id, name, WorkerStatus::Failed);
+                }
+                bus.dispatch(WorkerFailed {
+                    worker: name,
+                    error,
+                    restarts,
+                    backoff,
+                });
+            }
+            None => debug!("A task of worker `{}` ended before being shut down.", name),
+        }
+
+        let backoff = match backoff {
+            Some(backoff) => backoff,
+            None => return,
+        };
+
+        debug!("Restarting a task of worker `{}` in {:?}...", name, backoff);
+
+        if let Either::Left(_) = future::select(&mut shutdown, executor.sleep(backoff)).await {
+            return;
+        }
+
+        restarts += 1;
+    }
+}
@@ -3,7 +3,7 @@
 
 //! A module that deals with asynchronous workers in general.
 
-use crate::node::Node;
+use crate::{node::Node, status::Health};
 
 use async_trait::async_trait;
 
@@ -37,7 +37,7 @@ pub trait Worker<N: Node>: Any + Send + Sync + Sized {
     /// The configuration state required to start this worker.
     type Config;
     /// An error that may be emitted during node startup and shutdown.
-    type Error: std::error::Error;
+    type Error: std::error::Error + Send + 'static;
 
     /// Generate a list of `TypeId`s representing the topological worker dependencies of this worker.
     ///
@@ -54,4 +54,21 @@ pub trait Worker<N: Node>: Any + Send + Sync + Sized {
     async fn stop(self, _node: &mut N) -> Result<(), Self::Error> {
         Ok(())
     }
+
+    /// Attempt to apply a new configuration to this running worker, returning whether it was applied.
+    ///
+    /// Workers that can't be reconfigured, which is the default, are restarted with their new configuration instead.
+    async fn reconfigure(&mut self, _node: &mut N, _config: Self::Config) -> Result<bool, Self::Error>
+    where
+        Self::Config: Send,
+    {
+        Ok(false)
+    }
+
+    /// Check the health of this worker, e.g. for a [`StatusRegistry`](crate::status::StatusRegistry) snapshot.
+    ///
+    /// This check must be cheap and must not read the status registry itself.
+    fn health(&self) -> Health {
+        Health::Healthy
+    }
 }
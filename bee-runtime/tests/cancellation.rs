@@ -0,0 +1,61 @@
+// Copyright 2020-2021 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+use bee_runtime::{cancellation::CancellationToken, shutdown_future::ShutdownFuture};
+
+use futures::{channel::oneshot, future};
+
+#[test]
+fn hierarchy() {
+    let node = CancellationToken::new();
+    let worker_a = node.child();
+    let worker_b = node.child();
+    let task = worker_a.child();
+
+    worker_a.cancel();
+
+    assert!(worker_a.is_cancelled());
+    assert!(task.is_cancelled());
+    assert!(!node.is_cancelled());
+    assert!(!worker_b.is_cancelled());
+
+    node.cancel();
+
+    assert!(worker_b.is_cancelled());
+    assert!(node.child().is_cancelled());
+}
+
+#[tokio::test]
+async fn signals() {
+    let node = CancellationToken::new();
+    let task = node.child().child();
+    let signal = task.signal();
+    let cancelled = task.cancelled();
+
+    node.cancel();
+
+    assert_eq!(signal.await, Ok(()));
+    cancelled.await;
+    assert_eq!(task.signal().await, Ok(()));
+}
+
+#[tokio::test]
+async fn shutdown_future() {
+    let (_shutdown_sender, shutdown_receiver) = oneshot::channel::<()>();
+    assert_eq!(
+        ShutdownFuture::new(shutdown_receiver, future::ready(42)).await,
+        Some(42)
+    );
+
+    let (shutdown_sender, shutdown_receiver) = oneshot::channel::<()>();
+    shutdown_sender.send(()).unwrap();
+    assert_eq!(
+        ShutdownFuture::new(shutdown_receiver, future::pending::<()>()).await,
+        None
+    );
+
+    let token = CancellationToken::new();
+    let future = ShutdownFuture::new(token.signal(), future::pending::<()>());
+    token.cancel();
+    assert_eq!(future.await, None);
+}
@@ -0,0 +1,30 @@
+// Copyright 2020-2021 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+use bee_storage::backend::StorageBackend;
+
+use async_trait::async_trait;
+
+use std::convert::Infallible;
+
+/// A storage backend that stores nothing, for the tests that don't use storage.
+pub struct Backend;
+
+#[async_trait]
+impl StorageBackend for Backend {
+    type ConfigBuilder = ();
+    type Config = ();
+    type Error = Infallible;
+
+    async fn start(_config: Self::Config) -> Result<Self, Self::Error> {
+        Ok(Self)
+    }
+
+    async fn shutdown(self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+
+    async fn size(&self) -> Result<Option<usize>, Self::Error> {
+        Ok(None)
+    }
+}
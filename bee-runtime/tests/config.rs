@@ -0,0 +1,141 @@
+// Copyright 2020-2021 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+mod common;
+
+use common::Backend;
+
+use bee_runtime::{
+    config::{ConfigLoader, ConfiguredWorker, Error, Value},
+    node::BeeNode,
+    worker::Worker,
+};
+
+use async_trait::async_trait;
+use serde::Deserialize;
+
+use std::{convert::Infallible, time::Duration};
+
+type N = BeeNode<Backend>;
+
+#[derive(Default, Deserialize)]
+struct GossipConfigBuilder {
+    port: Option<u16>,
+    peers: Option<Vec<String>>,
+}
+
+#[derive(Debug, PartialEq)]
+struct GossipConfig {
+    port: u16,
+    peers: Vec<String>,
+}
+
+impl From<GossipConfigBuilder> for GossipConfig {
+    fn from(builder: GossipConfigBuilder) -> Self {
+        Self {
+            port: builder.port.unwrap_or(15600),
+            peers: builder.peers.unwrap_or_default(),
+        }
+    }
+}
+
+struct Gossip;
+
+#[async_trait]
+impl Worker<N> for Gossip {
+    type Config = GossipConfig;
+    type Error = Infallible;
+
+    async fn start(_node: &mut N, _config: Self::Config) -> Result<Self, Self::Error> {
+        Ok(Self)
+    }
+}
+
+impl ConfiguredWorker<N> for Gossip {
+    const SECTION: &'static str = "gossip";
+
+    type ConfigBuilder = GossipConfigBuilder;
+}
+
+const TOML: &str = r#"
+[node]
+stop_deadline_ms = 1000
+
+[workers.gossip]
+port = 1337
+peers = ["a", "b"]
+"#;
+
+#[test]
+fn layers() {
+    let config = ConfigLoader::new()
+        .toml(TOML)
+        .env_vars(
+            "BEE",
+            vec![
+                ("BEE_WORKERS__GOSSIP__PORT".to_owned(), "42".to_owned()),
+                ("OTHER_WORKERS__GOSSIP__PORT".to_owned(), "43".to_owned()),
+            ],
+        )
+        .set("node.stop_deadline_ms", 2000)
+        .load()
+        .unwrap();
+
+    assert_eq!(config.node().unwrap().stop_deadline(), Some(Duration::from_secs(2)));
+    assert_eq!(
+        config.worker::<_, Gossip>().unwrap(),
+        GossipConfig {
+            port: 42,
+            peers: vec!["a".to_owned(), "b".to_owned()],
+        }
+    );
+    assert_eq!(config.get("workers.gossip.port"), Some(&Value::Integer(42)));
+}
+
+#[test]
+fn missing_section() {
+    let config = ConfigLoader::new().load().unwrap();
+
+    assert_eq!(config.node().unwrap().stop_deadline(), None);
+    assert_eq!(
+        config.worker::<_, Gossip>().unwrap(),
+        GossipConfig {
+            port: 15600,
+            peers: Vec::new(),
+        }
+    );
+}
+
+#[test]
+fn invalid_value_path() {
+    let config = ConfigLoader::new()
+        .toml(TOML)
+        .env_vars(
+            "BEE",
+            vec![("BEE_WORKERS__GOSSIP__PEERS".to_owned(), "[1, 2]".to_owned())],
+        )
+        .load()
+        .unwrap();
+
+    match config.worker::<_, Gossip>() {
+        Err(Error::InvalidValue { path, .. }) => assert_eq!(path, "workers.gossip.peers[0]"),
+        _ => panic!("expected an invalid value"),
+    }
+
+    let config = ConfigLoader::new().set("workers.gossip.port", "high").load().unwrap();
+
+    match config.worker::<_, Gossip>() {
+        Err(Error::InvalidValue { path, .. }) => assert_eq!(path, "workers.gossip.port"),
+        _ => panic!("expected an invalid value"),
+    }
+}
+
+#[test]
+fn invalid_key() {
+    let result = ConfigLoader::new()
+        .set("node", 1)
+        .set("node.stop_deadline_ms", 1)
+        .load();
+
+    assert!(matches!(result, Err(Error::InvalidKey(key)) if key == "node.stop_deadline_ms"));
+}
@@ -1,10 +1,17 @@
 // Copyright 2020-2021 IOTA Stiftung
 // SPDX-License-Identifier: Apache-2.0
 
-use bee_runtime::event::Bus;
+use bee_runtime::event::{AsyncBus, Backpressure, Bus, Priority};
+
+use futures::{channel::oneshot, StreamExt};
+
+use std::{any::TypeId, sync::Mutex};
 
 struct Foo;
 
+#[derive(Clone, Debug, PartialEq)]
+struct Bar(usize);
+
 #[test]
 fn basic() {
     use std::sync::atomic::{AtomicBool, Ordering};
@@ -22,12 +29,143 @@ fn basic() {
     assert_eq!(received.load(Ordering::SeqCst), true);
 }
 
+#[test]
+fn priority() {
+    let order = Mutex::new(Vec::new());
+    let bus = Bus::default();
+
+    bus.add_listener::<(), _, _>(|_: &Foo| order.lock().unwrap().push("normal"));
+    bus.add_listener_with_priority::<(), _, _>(Priority(-1), |_: &Foo| order.lock().unwrap().push("low"));
+    bus.add_listener_with_priority::<(), _, _>(Priority(1), |_: &Foo| order.lock().unwrap().push("high"));
+    bus.add_listener::<(), _, _>(|_: &Foo| order.lock().unwrap().push("normal again"));
+
+    bus.dispatch(Foo);
+
+    drop(bus);
+
+    assert_eq!(*order.lock().unwrap(), vec!["high", "normal", "normal again", "low"]);
+}
+
+#[test]
+fn once() {
+    let count = Mutex::new(0);
+    let bus = Bus::default();
+
+    bus.add_once_listener::<(), _, _>(|_: &Foo| *count.lock().unwrap() += 1);
+
+    bus.dispatch(Foo);
+    bus.dispatch(Foo);
+
+    drop(bus);
+
+    assert_eq!(*count.lock().unwrap(), 1);
+}
+
+#[test]
+fn handle() {
+    let count = Mutex::new(0);
+    let bus = Bus::<TypeId>::default();
+
+    let handle = bus.add_listener_raw(TypeId::of::<()>(), |_: &Foo| *count.lock().unwrap() += 1);
+    bus.add_listener_raw(TypeId::of::<()>(), |_: &Foo| *count.lock().unwrap() += 10)
+        .detach();
+
+    bus.dispatch(Foo);
+    drop(handle);
+    bus.dispatch(Foo);
+
+    bus.remove_listeners_by_id(TypeId::of::<()>());
+    bus.dispatch(Foo);
+
+    drop(bus);
+
+    assert_eq!(*count.lock().unwrap(), 21);
+}
+
 #[test]
 fn send_sync() {
     fn helper<T: Send + Sync>() {}
     helper::<Bus<'static>>();
 }
 
+#[tokio::test]
+async fn async_basic() {
+    let bus = AsyncBus::default();
+
+    let mut subscription = bus.subscribe::<Bar>(4, Backpressure::Block);
+
+    bus.dispatch(Bar(1)).await;
+    bus.dispatch(Bar(2)).await;
+
+    assert_eq!(subscription.next().await, Some(Bar(1)));
+    assert_eq!(subscription.next().await, Some(Bar(2)));
+}
+
+#[tokio::test]
+async fn async_drop_when_full() {
+    let bus = AsyncBus::default();
+
+    let subscription = bus.subscribe::<Bar>(0, Backpressure::Drop);
+
+    for i in 0..10 {
+        bus.dispatch(Bar(i)).await;
+    }
+
+    drop(bus);
+
+    assert_eq!(subscription.collect::<Vec<_>>().await, vec![Bar(0)]);
+}
+
+#[tokio::test]
+async fn async_block_when_full() {
+    let bus = std::sync::Arc::new(AsyncBus::default());
+
+    let subscription = bus.subscribe::<Bar>(0, Backpressure::Block);
+
+    let dispatcher = {
+        let bus = bus.clone();
+        tokio::spawn(async move {
+            for i in 0..10 {
+                bus.dispatch(Bar(i)).await;
+            }
+        })
+    };
+
+    assert_eq!(
+        subscription.take(10).collect::<Vec<_>>().await,
+        (0..10).map(Bar).collect::<Vec<_>>()
+    );
+    dispatcher.await.unwrap();
+}
+
+#[tokio::test]
+async fn async_unsubscribe() {
+    let bus = AsyncBus::default();
+
+    let subscription = bus.subscribe::<Bar>(1, Backpressure::Block);
+    assert_eq!(bus.subscriber_count::<Bar>(), 1);
+
+    drop(subscription);
+    assert_eq!(bus.subscriber_count::<Bar>(), 0);
+
+    bus.dispatch(Bar(0)).await;
+}
+
+#[tokio::test]
+async fn async_subscribe_until_shutdown() {
+    let bus = AsyncBus::default();
+    let (shutdown_tx, shutdown_rx) = oneshot::channel();
+
+    let mut subscription = bus.subscribe_until::<Bar>(shutdown_rx, 4, Backpressure::Drop);
+
+    bus.dispatch(Bar(0)).await;
+    assert_eq!(subscription.next().await, Some(Bar(0)));
+
+    shutdown_tx.send(()).unwrap();
+    bus.dispatch(Bar(1)).await;
+    assert_eq!(subscription.next().await, None);
+}
+
 // TODO: Enable when stable
 // #[bench]
 // fn bench_add_two(b: &mut Bencher) {
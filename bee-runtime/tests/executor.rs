@@ -0,0 +1,133 @@
+// Copyright 2020-2021 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+mod common;
+
+use common::Backend;
+
+use bee_runtime::{
+    executor::{ExecutorExt, JoinError, LocalRuntime, TokioExecutor},
+    node::{BeeNode, BeeNodeBuilder, BeeNodeConfig, Node, NodeBuilder},
+    schedule::{self, MissedTickPolicy},
+    worker::Worker,
+};
+
+use async_trait::async_trait;
+
+use std::{
+    convert::Infallible,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+    thread::{self, ThreadId},
+    time::Duration,
+};
+
+type N = BeeNode<Backend>;
+
+#[derive(Clone, Default)]
+struct Probe {
+    ticks: Arc<AtomicUsize>,
+    threads: Arc<Mutex<Vec<ThreadId>>>,
+}
+
+struct Ticker;
+
+#[async_trait]
+impl Worker<N> for Ticker {
+    type Config = Probe;
+    type Error = Infallible;
+
+    async fn start(node: &mut N, config: Self::Config) -> Result<Self, Self::Error> {
+        let executor = node.executor();
+        let probe = config.clone();
+
+        schedule::spawn_interval::<Self, _, _, _>(node, Duration::from_millis(10), MissedTickPolicy::Skip, move || {
+            let probe = probe.clone();
+            let blocking = executor.spawn_blocking(|| thread::current().id());
+
+            async move {
+                probe.ticks.fetch_add(1, Ordering::SeqCst);
+                let blocking = blocking.await.unwrap();
+                probe
+                    .threads
+                    .lock()
+                    .unwrap()
+                    .extend(&[thread::current().id(), blocking]);
+            }
+        });
+
+        Ok(Self)
+    }
+}
+
+#[test]
+fn local_runtime_runs_node() {
+    let mut runtime = LocalRuntime::new();
+    let executor = runtime.executor();
+    let probe = Probe::default();
+
+    runtime.block_on(async {
+        let node = BeeNodeBuilder::<Backend>::new(BeeNodeConfig::default())
+            .unwrap()
+            .with_executor(executor.clone())
+            .with_worker_cfg::<Ticker>(probe.clone())
+            .finish()
+            .await
+            .unwrap();
+
+        executor.sleep(Duration::from_millis(100)).await;
+        node.stop().await.unwrap();
+    });
+
+    let ticks = probe.ticks.load(Ordering::SeqCst);
+    assert!((5..=11).contains(&ticks), "{} ticks", ticks);
+    assert!(probe
+        .threads
+        .lock()
+        .unwrap()
+        .iter()
+        .all(|id| *id == thread::current().id()));
+}
+
+#[tokio::test]
+async fn task_handles() {
+    let executor = TokioExecutor;
+
+    assert_eq!(executor.spawn(async { 42 }).await, Ok(42));
+    assert_eq!(executor.spawn_blocking(|| 42).await, Ok(42));
+    assert_eq!(
+        executor.spawn(async { panic!("oops") }).await,
+        Err::<(), _>(JoinError::Panicked("oops".to_owned()))
+    );
+    assert_eq!(
+        executor.spawn_blocking(|| panic!("blocking oops")).await,
+        Err::<(), _>(JoinError::Panicked("blocking oops".to_owned()))
+    );
+
+    let task = executor.spawn(futures::future::pending::<()>());
+    task.abort();
+    assert_eq!(task.await, Err(JoinError::Aborted));
+}
+
+#[test]
+fn local_timers_fire_in_order() {
+    let mut runtime = LocalRuntime::new();
+    let executor = runtime.executor();
+    let order = Arc::new(Mutex::new(Vec::new()));
+
+    for delay in [30u64, 10, 20].iter().copied() {
+        let order = order.clone();
+        let sleep = executor.sleep(Duration::from_millis(delay));
+
+        executor.spawn(async move {
+            sleep.await;
+            order.lock().unwrap().push(delay);
+        });
+    }
+
+    runtime.block_on(executor.sleep(Duration::from_millis(50)));
+
+    assert_eq!(*order.lock().unwrap(), vec![10, 20, 30]);
+}
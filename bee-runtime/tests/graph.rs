@@ -0,0 +1,67 @@
+// Copyright 2020-2021 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+use bee_runtime::graph::{Error, WorkerGraph};
+
+use std::any::TypeId;
+
+struct A;
+struct B;
+struct C;
+
+fn deps<T: 'static>() -> &'static [TypeId] {
+    Box::leak(Box::from(vec![TypeId::of::<T>()]))
+}
+
+#[test]
+fn startup_order() {
+    let mut graph = WorkerGraph::new();
+
+    graph.add_raw(TypeId::of::<C>(), "c", deps::<B>());
+    graph.add_raw(TypeId::of::<B>(), "b", deps::<A>());
+    graph.add_raw(TypeId::of::<A>(), "a", &[]);
+
+    assert_eq!(
+        graph.startup_order(),
+        Ok(vec![TypeId::of::<A>(), TypeId::of::<B>(), TypeId::of::<C>()])
+    );
+}
+
+#[test]
+fn missing_dependency() {
+    let mut graph = WorkerGraph::new();
+
+    graph.add_raw(TypeId::of::<B>(), "b", deps::<A>());
+
+    assert_eq!(
+        graph.validate(),
+        Err(Error::MissingDependency {
+            worker: "b",
+            dependency: TypeId::of::<A>(),
+        })
+    );
+}
+
+#[test]
+fn cycle() {
+    let mut graph = WorkerGraph::new();
+
+    graph.add_raw(TypeId::of::<A>(), "a", deps::<C>());
+    graph.add_raw(TypeId::of::<B>(), "b", deps::<A>());
+    graph.add_raw(TypeId::of::<C>(), "c", deps::<B>());
+
+    assert_eq!(graph.validate(), Err(Error::Cycle(vec!["a", "c", "b"])));
+}
+
+#[test]
+fn dot() {
+    let mut graph = WorkerGraph::new();
+
+    graph.add_raw(TypeId::of::<B>(), "b", deps::<A>());
+    graph.add_raw(TypeId::of::<A>(), "a", &[]);
+
+    assert_eq!(
+        graph.to_dot(),
+        "digraph workers {\n    \"b\" [label=\"2. b\"];\n    \"a\" [label=\"1. a\"];\n    \"b\" -> \"a\";\n}\n"
+    );
+}
@@ -0,0 +1,149 @@
+// Copyright 2020-2021 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+use bee_common::packable::{Packable, Read, Write};
+use bee_runtime::event::{Bus, JournalEntry, JournalError, Recorder, Replayer};
+
+use std::{any::TypeId, sync::Mutex};
+
+#[derive(Debug, PartialEq)]
+struct Foo(u32);
+
+impl Packable for Foo {
+    type Error = std::io::Error;
+
+    fn packed_len(&self) -> usize {
+        self.0.packed_len()
+    }
+
+    fn pack<W: Write>(&self, writer: &mut W) -> Result<(), Self::Error> {
+        self.0.pack(writer)
+    }
+
+    fn unpack<R: Read + ?Sized>(reader: &mut R) -> Result<Self, Self::Error> {
+        Ok(Self(u32::unpack(reader)?))
+    }
+}
+
+#[derive(Debug, PartialEq)]
+struct Bar(bool);
+
+impl Packable for Bar {
+    type Error = std::io::Error;
+
+    fn packed_len(&self) -> usize {
+        self.0.packed_len()
+    }
+
+    fn pack<W: Write>(&self, writer: &mut W) -> Result<(), Self::Error> {
+        self.0.pack(writer)
+    }
+
+    fn unpack<R: Read + ?Sized>(reader: &mut R) -> Result<Self, Self::Error> {
+        Ok(Self(bool::unpack(reader)?))
+    }
+}
+
+struct NotRecorded;
+
+fn record() -> Vec<u8> {
+    let bus = Bus::default();
+    let recorder = Recorder::new(Vec::new());
+
+    let foo_handle = recorder.record::<Foo, _>(&bus, TypeId::of::<()>());
+    let bar_handle = recorder.record::<Bar, _>(&bus, TypeId::of::<()>());
+
+    bus.dispatch(Foo(1));
+    bus.dispatch(NotRecorded);
+    bus.dispatch(Bar(true));
+    bus.dispatch(Foo(2));
+
+    drop(foo_handle);
+    drop(bar_handle);
+
+    recorder.into_inner().unwrap()
+}
+
+#[test]
+fn record_entries() {
+    let journal = record();
+    let mut reader = journal.as_slice();
+    let mut entries = Vec::new();
+
+    while let Some(entry) = JournalEntry::read(&mut reader).unwrap() {
+        entries.push(entry);
+    }
+
+    assert_eq!(entries.len(), 3);
+    assert!(entries[0].type_name.ends_with("Foo"));
+    assert_eq!(entries[0].payload, 1u32.pack_new());
+    assert!(entries[1].type_name.ends_with("Bar"));
+    assert!(entries[0].timestamp <= entries[2].timestamp);
+    assert_eq!(entries.iter().map(|e| e.packed_len()).sum::<usize>(), journal.len());
+}
+
+#[test]
+fn replay() {
+    let journal = record();
+    let replayed = Mutex::new(Vec::new());
+    let bus = Bus::default();
+
+    bus.add_static_listener(|event: &Foo| replayed.lock().unwrap().push(format!("{:?}", event)));
+    bus.add_static_listener(|event: &Bar| replayed.lock().unwrap().push(format!("{:?}", event)));
+
+    let count = Replayer::new()
+        .with_event::<Foo>()
+        .with_event::<Bar>()
+        .replay(&mut journal.as_slice(), &bus)
+        .unwrap();
+
+    drop(bus);
+
+    assert_eq!(count, 3);
+    assert_eq!(*replayed.lock().unwrap(), vec!["Foo(1)", "Bar(true)", "Foo(2)"]);
+}
+
+#[test]
+fn replay_unknown_event() {
+    let journal = record();
+    let bus = Bus::<TypeId>::default();
+
+    let result = Replayer::new()
+        .with_event::<Foo>()
+        .replay(&mut journal.as_slice(), &bus);
+
+    assert!(matches!(result, Err(JournalError::UnknownEvent(name)) if name.ends_with("Bar")));
+}
+
+#[test]
+fn pack_too_long() {
+    let entry = JournalEntry {
+        timestamp: 0,
+        type_name: "a".repeat(u16::MAX as usize + 1),
+        payload: Vec::new(),
+    };
+    let mut journal = Vec::new();
+
+    assert!(matches!(
+        entry.pack(&mut journal),
+        Err(JournalError::TooLong("type name", len)) if len == u16::MAX as usize + 1
+    ));
+    assert!(journal.is_empty());
+}
+
+#[test]
+fn unpack_truncated_payload() {
+    let mut journal = Vec::new();
+
+    0u64.pack(&mut journal).unwrap();
+    1u16.pack(&mut journal).unwrap();
+    journal.push(b'a');
+    // The payload claims the maximum length, but is cut short.
+    u32::MAX.pack(&mut journal).unwrap();
+    journal.extend_from_slice(&[0; 8]);
+
+    assert!(matches!(
+        JournalEntry::read(&mut journal.as_slice()),
+        Err(JournalError::Io(e)) if e.kind() == std::io::ErrorKind::UnexpectedEof
+    ));
+}
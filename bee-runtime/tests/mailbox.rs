@@ -0,0 +1,163 @@
+// Copyright 2020-2021 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+mod common;
+
+use common::Backend;
+
+use bee_runtime::{
+    mailbox::{self, Address, Error, Message},
+    node::{BeeNode, BeeNodeBuilder, BeeNodeConfig, Node, NodeBuilder},
+    worker::Worker,
+};
+
+use async_trait::async_trait;
+use tokio::time::sleep;
+
+use std::{
+    convert::Infallible,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+type N = BeeNode<Backend>;
+
+struct Add(u64);
+
+impl Message for Add {
+    type Response = u64;
+}
+
+struct Wait(Duration);
+
+impl Message for Wait {
+    type Response = ();
+}
+
+struct Counter {
+    stopped: Arc<AtomicBool>,
+}
+
+#[async_trait]
+impl Worker<N> for Counter {
+    type Config = Arc<AtomicBool>;
+    type Error = Infallible;
+
+    async fn start(node: &mut N, config: Self::Config) -> Result<Self, Self::Error> {
+        let stopped = config.clone();
+        let mut total = 0;
+
+        mailbox::open::<Self, _, _, _, _>(node, 8, move |Add(value)| {
+            // No message may be handled once the worker has been stopped.
+            assert!(!stopped.load(Ordering::SeqCst));
+            total += value;
+            async move { total }
+        });
+
+        mailbox::open::<Self, _, _, _, _>(node, 1, |Wait(duration)| sleep(duration));
+
+        Ok(Self { stopped: config })
+    }
+
+    async fn stop(self, _node: &mut N) -> Result<(), Self::Error> {
+        self.stopped.store(true, Ordering::SeqCst);
+
+        Ok(())
+    }
+}
+
+struct Unbuffered;
+
+#[async_trait]
+impl Worker<N> for Unbuffered {
+    type Config = ();
+    type Error = Infallible;
+
+    async fn start(node: &mut N, _config: Self::Config) -> Result<Self, Self::Error> {
+        mailbox::open::<Self, _, _, _, _>(node, 0, |Add(value)| async move { value });
+
+        Ok(Self)
+    }
+}
+
+#[tokio::test]
+async fn send_and_ask() {
+    let node = BeeNodeBuilder::<Backend>::new(BeeNodeConfig::default())
+        .unwrap()
+        .with_worker_cfg::<Counter>(Default::default())
+        .finish()
+        .await
+        .unwrap();
+
+    let address = Address::<Add>::clone(&node.resource());
+
+    address.send(Add(1)).await.unwrap();
+    address.clone().try_send(Add(2)).unwrap();
+    assert_eq!(address.ask(Add(3)).await, Ok(6));
+
+    node.stop().await.unwrap();
+
+    assert!(address.is_closed());
+    assert_eq!(address.send(Add(4)).await, Err(Error::Closed));
+    assert_eq!(address.ask(Add(5)).await, Err(Error::Closed));
+}
+
+#[tokio::test]
+async fn bounded() {
+    let node = BeeNodeBuilder::<Backend>::new(BeeNodeConfig::default())
+        .unwrap()
+        .with_worker_cfg::<Counter>(Default::default())
+        .finish()
+        .await
+        .unwrap();
+
+    let address = Address::<Wait>::clone(&node.resource());
+
+    // The first message is being handled, the second one fills the mailbox.
+    address.send(Wait(Duration::from_millis(200))).await.unwrap();
+    sleep(Duration::from_millis(20)).await;
+    address.send(Wait(Duration::ZERO)).await.unwrap();
+
+    assert_eq!(address.try_send(Wait(Duration::ZERO)), Err(Error::Full));
+
+    node.stop().await.unwrap();
+
+    assert_eq!(address.try_send(Wait(Duration::ZERO)), Err(Error::Closed));
+}
+
+#[tokio::test]
+async fn queued_messages_dropped_on_stop() {
+    let node = BeeNodeBuilder::<Backend>::new(BeeNodeConfig::default())
+        .unwrap()
+        .with_worker_cfg::<Counter>(Default::default())
+        .finish()
+        .await
+        .unwrap();
+
+    let address = Address::<Wait>::clone(&node.resource());
+
+    address.send(Wait(Duration::from_millis(200))).await.unwrap();
+    sleep(Duration::from_millis(20)).await;
+    let queued = tokio::spawn({
+        let address = address.clone();
+        async move { address.ask(Wait(Duration::ZERO)).await }
+    });
+    sleep(Duration::from_millis(20)).await;
+
+    node.stop().await.unwrap();
+
+    assert_eq!(queued.await.unwrap(), Err(Error::Closed));
+}
+
+#[tokio::test]
+#[should_panic(expected = "the capacity of a mailbox must be non-zero")]
+async fn zero_capacity() {
+    let _ = BeeNodeBuilder::<Backend>::new(BeeNodeConfig::default())
+        .unwrap()
+        .with_worker::<Unbuffered>()
+        .finish()
+        .await;
+}
@@ -0,0 +1,149 @@
+// Copyright 2020-2021 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+mod common;
+
+use common::Backend;
+
+use bee_runtime::{
+    event::Bus,
+    metrics::{MetricsExporter, MetricsExporterConfigBuilder, MetricsRegistry},
+    node::{BeeNode, BeeNodeBuilder, BeeNodeConfig, Node, NodeBuilder},
+    worker::Worker,
+};
+
+use async_trait::async_trait;
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::TcpStream,
+};
+
+use std::{any::type_name, convert::Infallible, net::SocketAddr};
+
+type N = BeeNode<Backend>;
+
+struct Ping;
+
+struct Pinger;
+
+#[async_trait]
+impl Worker<N> for Pinger {
+    type Config = ();
+    type Error = Infallible;
+
+    async fn start(node: &mut N, _config: Self::Config) -> Result<Self, Self::Error> {
+        node.resource::<MetricsRegistry>()
+            .worker::<Self>()
+            .counter("pings_total", "Number of pings.")
+            .inc_by(3);
+
+        Ok(Self)
+    }
+}
+
+async fn get(address: SocketAddr, path: &str) -> String {
+    let mut stream = TcpStream::connect(address).await.unwrap();
+    let mut response = String::new();
+
+    stream
+        .write_all(format!("GET {} HTTP/1.1\r\nHost: localhost\r\n\r\n", path).as_bytes())
+        .await
+        .unwrap();
+    stream.read_to_string(&mut response).await.unwrap();
+
+    response
+}
+
+#[test]
+fn render() {
+    let metrics = MetricsRegistry::new();
+
+    metrics.counter("requests_total", "Number of requests.", &[]).inc();
+    metrics
+        .counter("requests_total", "Number of requests.", &[("path", "/a\"b")])
+        .inc_by(2);
+    metrics.gauge("peers", "Number of peers.", &[("kind", "known")]).set(-4);
+    metrics.computed_gauge("answer", "The answer.", &[], || Some(42));
+    metrics.computed_gauge("gone", "Nothing.", &[], || None);
+
+    let latency = metrics.histogram("latency_seconds", "Latency.\nIn seconds.", &[], &[1.0, 0.1]);
+    latency.observe(0.05);
+    latency.observe(0.5);
+    latency.observe(2.0);
+    // The buckets of an existing histogram are kept.
+    assert_eq!(metrics.histogram("latency_seconds", "", &[], &[]).count(), 3);
+
+    assert_eq!(
+        metrics.render(),
+        "# HELP answer The answer.\n\
+         # TYPE answer gauge\n\
+         answer 42\n\
+         # HELP gone Nothing.\n\
+         # TYPE gone gauge\n\
+         # HELP latency_seconds Latency.\\nIn seconds.\n\
+         # TYPE latency_seconds histogram\n\
+         latency_seconds_bucket{le=\"0.1\"} 1\n\
+         latency_seconds_bucket{le=\"1\"} 2\n\
+         latency_seconds_bucket{le=\"+Inf\"} 3\n\
+         latency_seconds_sum 2.55\n\
+         latency_seconds_count 3\n\
+         # HELP peers Number of peers.\n\
+         # TYPE peers gauge\n\
+         peers{kind=\"known\"} -4\n\
+         # HELP requests_total Number of requests.\n\
+         # TYPE requests_total counter\n\
+         requests_total 1\n\
+         requests_total{path=\"/a\\\"b\"} 2\n"
+    );
+}
+
+#[test]
+#[should_panic(expected = "already registered as a counter")]
+fn type_mismatch() {
+    let metrics = MetricsRegistry::new();
+
+    metrics.counter("requests", "", &[]);
+    metrics.gauge("requests", "", &[]);
+}
+
+#[tokio::test]
+async fn exporter() {
+    let node = BeeNodeBuilder::<Backend>::new(BeeNodeConfig::default())
+        .unwrap()
+        .with_worker::<Pinger>()
+        .with_worker_cfg::<MetricsExporter>(
+            MetricsExporterConfigBuilder::new()
+                .bind_address(([127, 0, 0, 1], 0).into())
+                .finish(),
+        )
+        .finish()
+        .await
+        .unwrap();
+
+    let address = node.worker::<MetricsExporter>().unwrap().local_addr();
+    let bus = node.bus();
+
+    bus.dispatch(Ping);
+    bus.dispatch(Ping);
+
+    let response = get(address, "/metrics").await;
+
+    assert!(response.starts_with("HTTP/1.1 200 OK\r\n"));
+    assert!(response.contains(&format!(
+        "bee_bus_dispatches_total{{event=\"{}\"}} 2\n",
+        type_name::<Ping>()
+    )));
+    assert!(response.contains(&format!("pings_total{{worker=\"{}\"}} 3\n", type_name::<Pinger>())));
+    // The handle to the bus above is the only live one.
+    assert!(response.contains(&format!(
+        "bee_resource_handles{{resource=\"{}\"}} 1\n",
+        type_name::<Bus<'static>>()
+    )));
+
+    assert!(get(address, "/").await.starts_with("HTTP/1.1 404 Not Found\r\n"));
+
+    drop(bus);
+    node.stop().await.unwrap();
+
+    assert!(TcpStream::connect(address).await.is_err());
+}
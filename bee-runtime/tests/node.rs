@@ -0,0 +1,395 @@
+// Copyright 2020-2021 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+mod common;
+
+use common::Backend;
+
+use bee_runtime::{
+    graph,
+    node::{BeeNode, BeeNodeBuilder, BeeNodeConfig, Error, Node, NodeBuilder},
+    worker::Worker,
+};
+
+use async_trait::async_trait;
+
+use std::{
+    any::TypeId,
+    convert::Infallible,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+type N = BeeNode<Backend>;
+
+#[derive(Clone, Default)]
+struct Journal(Arc<Mutex<Vec<&'static str>>>);
+
+impl Journal {
+    fn push(&self, entry: &'static str) {
+        self.0.lock().unwrap().push(entry);
+    }
+
+    fn entries(&self) -> Vec<&'static str> {
+        self.0.lock().unwrap().clone()
+    }
+}
+
+struct A;
+
+#[async_trait]
+impl Worker<N> for A {
+    type Config = ();
+    type Error = Infallible;
+
+    async fn start(node: &mut N, _config: Self::Config) -> Result<Self, Self::Error> {
+        let journal = node.resource::<Journal>();
+        journal.push("start a");
+
+        node.spawn::<Self, _, _>(|shutdown| async move {
+            let _ = shutdown.await;
+            journal.push("task a");
+        });
+
+        Ok(Self)
+    }
+
+    async fn stop(self, node: &mut N) -> Result<(), Self::Error> {
+        node.resource::<Journal>().push("stop a");
+        Ok(())
+    }
+}
+
+struct B;
+
+#[async_trait]
+impl Worker<N> for B {
+    type Config = ();
+    type Error = Infallible;
+
+    fn dependencies() -> &'static [TypeId] {
+        Box::leak(Box::from(vec![TypeId::of::<A>()]))
+    }
+
+    async fn start(node: &mut N, _config: Self::Config) -> Result<Self, Self::Error> {
+        assert!(node.worker::<A>().is_some());
+        node.resource::<Journal>().push("start b");
+        Ok(Self)
+    }
+
+    async fn stop(self, node: &mut N) -> Result<(), Self::Error> {
+        node.resource::<Journal>().push("stop b");
+        Ok(())
+    }
+}
+
+struct C;
+
+#[async_trait]
+impl Worker<N> for C {
+    type Config = ();
+    type Error = Infallible;
+
+    fn dependencies() -> &'static [TypeId] {
+        Box::leak(Box::from(vec![TypeId::of::<D>()]))
+    }
+
+    async fn start(_node: &mut N, _config: Self::Config) -> Result<Self, Self::Error> {
+        Ok(Self)
+    }
+}
+
+struct D;
+
+#[async_trait]
+impl Worker<N> for D {
+    type Config = ();
+    type Error = Infallible;
+
+    fn dependencies() -> &'static [TypeId] {
+        Box::leak(Box::from(vec![TypeId::of::<C>()]))
+    }
+
+    async fn start(_node: &mut N, _config: Self::Config) -> Result<Self, Self::Error> {
+        Ok(Self)
+    }
+}
+
+struct Stubborn;
+
+#[async_trait]
+impl Worker<N> for Stubborn {
+    type Config = ();
+    type Error = Infallible;
+
+    async fn start(node: &mut N, _config: Self::Config) -> Result<Self, Self::Error> {
+        let journal = node.resource::<Journal>();
+
+        node.spawn::<Self, _, _>(|_shutdown| async move {
+            futures::future::pending::<()>().await;
+            journal.push("task stubborn");
+        });
+
+        Ok(Self)
+    }
+
+    async fn stop(self, node: &mut N) -> Result<(), Self::Error> {
+        node.resource::<Journal>().push("stop stubborn");
+        Ok(())
+    }
+}
+
+struct Faulty;
+
+#[async_trait]
+impl Worker<N> for Faulty {
+    type Config = ();
+    type Error = std::io::Error;
+
+    async fn start(_node: &mut N, _config: Self::Config) -> Result<Self, Self::Error> {
+        Ok(Self)
+    }
+
+    async fn stop(self, _node: &mut N) -> Result<(), Self::Error> {
+        Err(std::io::Error::other("faulty"))
+    }
+}
+
+struct Ping;
+
+struct Export;
+
+struct Exporter;
+
+#[async_trait]
+impl Worker<N> for Exporter {
+    type Config = ();
+    type Error = Infallible;
+
+    fn dependencies() -> &'static [TypeId] {
+        Box::leak(Box::from(vec![TypeId::of::<A>()]))
+    }
+
+    async fn start(node: &mut N, _config: Self::Config) -> Result<Self, Self::Error> {
+        let journal = node.resource::<Journal>();
+
+        node.register_resource(Export);
+        node.bus()
+            .add_listener::<Self, Ping, _>(move |_: &Ping| journal.push("ping"));
+
+        Ok(Self)
+    }
+
+    async fn stop(self, node: &mut N) -> Result<(), Self::Error> {
+        node.resource::<Journal>().push("stop exporter");
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn topological_start_and_stop() {
+    let journal = Journal::default();
+
+    let node = BeeNodeBuilder::<Backend>::new(BeeNodeConfig::default())
+        .unwrap()
+        .with_worker::<B>()
+        .with_worker::<A>()
+        .with_resource(journal.clone())
+        .finish()
+        .await
+        .unwrap();
+
+    assert!(node.worker::<A>().is_some());
+    assert!(node.worker::<B>().is_some());
+
+    node.stop().await.unwrap();
+
+    assert_eq!(
+        journal.entries(),
+        vec!["start a", "start b", "stop b", "task a", "stop a"]
+    );
+}
+
+#[tokio::test]
+async fn cyclic_dependency() {
+    let result = BeeNodeBuilder::<Backend>::new(BeeNodeConfig::default())
+        .unwrap()
+        .with_worker::<C>()
+        .with_worker::<D>()
+        .finish()
+        .await;
+
+    assert!(matches!(result, Err(Error::Graph(graph::Error::Cycle(_)))));
+}
+
+#[tokio::test]
+async fn resources() {
+    let mut node = BeeNodeBuilder::<Backend>::new(BeeNodeConfig::default())
+        .unwrap()
+        .finish()
+        .await
+        .unwrap();
+
+    node.register_resource(42u32);
+    assert_eq!(*node.resource::<u32>(), 42);
+
+    let handle = node.resource::<u32>();
+    assert_eq!(node.remove_resource::<u32>(), None);
+    // A resource that is still in use stays registered.
+    assert_eq!(*node.resource::<u32>(), 42);
+    drop(handle);
+
+    assert_eq!(node.remove_resource::<u32>(), Some(42));
+    assert_eq!(node.remove_resource::<u32>(), None);
+
+    node.stop().await.unwrap();
+}
+
+#[tokio::test]
+async fn leak_report() {
+    let mut node = BeeNodeBuilder::<Backend>::new(BeeNodeConfig::default())
+        .unwrap()
+        .finish()
+        .await
+        .unwrap();
+
+    node.register_resource(42u32);
+    let leaked = node.resource::<u32>();
+
+    let (report, error) = node.stop_with_report().await;
+    assert!(error.is_none());
+
+    assert!(!report.is_clean());
+    assert_eq!(report.leaked_resources.len(), 1);
+    assert_eq!(report.leaked_resources[0].type_name, "u32");
+    assert_eq!(report.leaked_resources[0].handles.len(), 1);
+
+    drop(leaked);
+}
+
+#[tokio::test]
+async fn leak_report_on_stop_failure() {
+    let mut node = BeeNodeBuilder::<Backend>::new(BeeNodeConfig::default())
+        .unwrap()
+        .with_worker::<Faulty>()
+        .finish()
+        .await
+        .unwrap();
+
+    node.register_resource(42u32);
+    let leaked = node.resource::<u32>();
+
+    let (report, error) = node.stop_with_report().await;
+
+    assert!(matches!(error, Some(Error::WorkerStop(_, _))));
+    assert_eq!(report.leaked_resources.len(), 1);
+    assert_eq!(report.leaked_resources[0].type_name, "u32");
+
+    drop(leaked);
+}
+
+#[tokio::test]
+async fn cancellation() {
+    let journal = Journal::default();
+
+    let node = BeeNodeBuilder::<Backend>::new(BeeNodeConfig::default())
+        .unwrap()
+        .with_worker::<A>()
+        .with_resource(journal.clone())
+        .finish()
+        .await
+        .unwrap();
+
+    node.cancellation_token().cancel();
+    tokio::time::sleep(Duration::from_millis(10)).await;
+
+    assert_eq!(journal.entries(), vec!["start a", "task a"]);
+
+    node.stop().await.unwrap();
+}
+
+#[tokio::test]
+async fn stop_deadline() {
+    let journal = Journal::default();
+    let config = BeeNodeConfig::build().stop_deadline(Duration::from_millis(50)).finish();
+
+    let node = BeeNodeBuilder::<Backend>::new(config)
+        .unwrap()
+        .with_worker::<A>()
+        .with_worker::<Stubborn>()
+        .with_resource(journal.clone())
+        .finish()
+        .await
+        .unwrap();
+
+    let (report, error) = node.stop_with_report().await;
+    assert!(error.is_none());
+
+    assert_eq!(report.overruns.len(), 1);
+    assert_eq!(report.overruns[0].worker, std::any::type_name::<Stubborn>());
+    assert_eq!(report.overruns[0].deadline, Duration::from_millis(50));
+    assert_eq!(report.overruns[0].aborted_tasks, 1);
+    assert_eq!(journal.entries(), vec!["start a", "task a", "stop a"]);
+}
+
+#[tokio::test]
+async fn add_and_remove_workers() {
+    let journal = Journal::default();
+
+    let mut node = BeeNodeBuilder::<Backend>::new(BeeNodeConfig::default())
+        .unwrap()
+        .with_resource(journal.clone())
+        .finish()
+        .await
+        .unwrap();
+
+    assert!(matches!(
+        node.add_worker::<Exporter>(()).await,
+        Err(Error::UnknownWorkerDependency(_, dependency)) if dependency == TypeId::of::<A>()
+    ));
+
+    node.add_worker::<A>(()).await.unwrap();
+    node.add_worker::<Exporter>(()).await.unwrap();
+    assert!(matches!(node.add_worker::<A>(()).await, Err(Error::WorkerRunning(_))));
+    assert!(matches!(
+        node.remove_worker::<A>().await,
+        Err(Error::WorkerRequired(_, _))
+    ));
+
+    node.bus().dispatch(Ping);
+    let report = node.remove_worker::<Exporter>().await.unwrap();
+    node.bus().dispatch(Ping);
+
+    assert!(report.is_clean());
+    assert!(node.worker::<Exporter>().is_none());
+    assert!(node.remove_resource::<Export>().is_none());
+    assert!(matches!(
+        node.remove_worker::<Exporter>().await,
+        Err(Error::WorkerNotRunning(_))
+    ));
+
+    node.remove_worker::<A>().await.unwrap();
+    assert!(matches!(
+        node.add_worker::<Exporter>(()).await,
+        Err(Error::WorkerDependency(_, dependency)) if dependency == std::any::type_name::<A>()
+    ));
+
+    node.add_worker::<A>(()).await.unwrap();
+    node.add_worker::<Exporter>(()).await.unwrap();
+    node.stop().await.unwrap();
+
+    assert_eq!(
+        journal.entries(),
+        vec![
+            "start a",
+            "ping",
+            "stop exporter",
+            "task a",
+            "stop a",
+            "start a",
+            "stop exporter",
+            "task a",
+            "stop a"
+        ]
+    );
+}
@@ -0,0 +1,269 @@
+// Copyright 2020-2021 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+mod common;
+
+use common::Backend;
+
+use bee_runtime::{
+    config::{ConfigLoader, ConfiguredWorker},
+    node::{BeeNode, BeeNodeBuilder, Error, Node, NodeBuilder},
+    reload::{ConfigWatcher, ConfigWatcherConfig, ReloadRequests},
+    signal::ReloadRequested,
+    worker::Worker,
+};
+
+use async_trait::async_trait;
+use futures::StreamExt;
+use serde::Deserialize;
+use tokio::time::{sleep, timeout};
+
+use std::{
+    any::{type_name, TypeId},
+    convert::Infallible,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+type N = BeeNode<Backend>;
+
+#[derive(Clone, Default)]
+struct Journal(Arc<Mutex<Vec<String>>>);
+
+impl Journal {
+    fn push(&self, entry: String) {
+        self.0.lock().unwrap().push(entry);
+    }
+
+    fn take(&self) -> Vec<String> {
+        std::mem::take(&mut *self.0.lock().unwrap())
+    }
+}
+
+#[derive(Default, Deserialize)]
+struct LevelConfigBuilder {
+    level: Option<u8>,
+}
+
+impl From<LevelConfigBuilder> for u8 {
+    fn from(builder: LevelConfigBuilder) -> Self {
+        builder.level.unwrap_or(0)
+    }
+}
+
+struct Tunable(u8);
+
+#[async_trait]
+impl Worker<N> for Tunable {
+    type Config = u8;
+    type Error = Infallible;
+
+    async fn start(node: &mut N, level: Self::Config) -> Result<Self, Self::Error> {
+        node.resource::<Journal>().push(format!("start tunable {}", level));
+        Ok(Self(level))
+    }
+
+    async fn reconfigure(&mut self, node: &mut N, level: Self::Config) -> Result<bool, Self::Error> {
+        node.resource::<Journal>()
+            .push(format!("reconfigure tunable {}", level));
+        self.0 = level;
+        Ok(true)
+    }
+}
+
+impl ConfiguredWorker<N> for Tunable {
+    const SECTION: &'static str = "tunable";
+
+    type ConfigBuilder = LevelConfigBuilder;
+}
+
+struct Ping;
+
+struct Rigid;
+
+#[async_trait]
+impl Worker<N> for Rigid {
+    type Config = u8;
+    type Error = Infallible;
+
+    async fn start(node: &mut N, level: Self::Config) -> Result<Self, Self::Error> {
+        let journal = node.resource::<Journal>();
+
+        journal.push(format!("start rigid {}", level));
+        node.bus().add_listener::<Self, Ping, _>(move |_| {
+            journal.push(format!("ping rigid {}", level));
+        });
+
+        Ok(Self)
+    }
+
+    async fn stop(self, node: &mut N) -> Result<(), Self::Error> {
+        node.resource::<Journal>().push("stop rigid".to_owned());
+        Ok(())
+    }
+}
+
+impl ConfiguredWorker<N> for Rigid {
+    const SECTION: &'static str = "rigid";
+
+    type ConfigBuilder = LevelConfigBuilder;
+}
+
+struct Dependent;
+
+#[async_trait]
+impl Worker<N> for Dependent {
+    type Config = ();
+    type Error = Infallible;
+
+    fn dependencies() -> &'static [TypeId] {
+        Box::leak(Box::from(vec![TypeId::of::<Rigid>()]))
+    }
+
+    async fn start(node: &mut N, _config: Self::Config) -> Result<Self, Self::Error> {
+        node.resource::<Journal>().push("start dependent".to_owned());
+        Ok(Self)
+    }
+
+    async fn stop(self, node: &mut N) -> Result<(), Self::Error> {
+        node.resource::<Journal>().push("stop dependent".to_owned());
+        Ok(())
+    }
+}
+
+fn config(tunable: u8, rigid: u8) -> ConfigLoader {
+    ConfigLoader::new()
+        .set("workers.tunable.level", tunable as i64)
+        .set("workers.rigid.level", rigid as i64)
+}
+
+#[tokio::test]
+async fn reconfigure_and_restart() {
+    let journal = Journal::default();
+
+    let mut node = BeeNodeBuilder::<Backend>::from_config(config(1, 1).load().unwrap())
+        .unwrap()
+        .with_resource(journal.clone())
+        .with_configured_worker::<Tunable>()
+        .with_configured_worker::<Rigid>()
+        .with_worker::<Dependent>()
+        .finish()
+        .await
+        .unwrap();
+
+    assert_eq!(
+        journal.take(),
+        vec!["start tunable 1", "start rigid 1", "start dependent"]
+    );
+
+    let report = node.reload(config(1, 1).load().unwrap()).await.unwrap();
+    assert!(report.reconfigured.is_empty());
+    assert!(report.restarted.is_empty());
+    assert!(journal.take().is_empty());
+
+    let report = node.reload(config(2, 2).load().unwrap()).await.unwrap();
+    assert_eq!(report.reconfigured, vec![type_name::<Tunable>()]);
+    assert_eq!(report.restarted, vec![type_name::<Rigid>(), type_name::<Dependent>()]);
+    assert!(report.leaked_resources.is_empty());
+    assert_eq!(
+        journal.take(),
+        vec![
+            "reconfigure tunable 2",
+            "stop dependent",
+            "stop rigid",
+            "start rigid 2",
+            "start dependent"
+        ]
+    );
+    assert_eq!(node.worker::<Tunable>().unwrap().0, 2);
+
+    // The listener of the stopped worker was removed before it was started again.
+    node.bus().dispatch(Ping);
+    assert_eq!(journal.take(), vec!["ping rigid 2"]);
+
+    node.stop().await.unwrap();
+
+    assert_eq!(journal.take(), vec!["stop dependent", "stop rigid"]);
+}
+
+#[tokio::test]
+async fn failed_reload_keeps_config() {
+    let journal = Journal::default();
+
+    let mut node = BeeNodeBuilder::<Backend>::from_config(config(1, 1).load().unwrap())
+        .unwrap()
+        .with_resource(journal.clone())
+        .with_configured_worker::<Tunable>()
+        .with_configured_worker::<Rigid>()
+        .finish()
+        .await
+        .unwrap();
+
+    journal.take();
+
+    let invalid = config(1, 2).set("workers.tunable.level", "high").load().unwrap();
+    assert!(matches!(node.reload(invalid).await, Err(Error::Config(_))));
+    assert!(journal.take().is_empty());
+
+    // The failed configuration was not committed, so the current one still doesn't change anything.
+    let report = node.reload(config(1, 1).load().unwrap()).await.unwrap();
+    assert!(report.reconfigured.is_empty());
+    assert!(report.restarted.is_empty());
+
+    let report = node.reload(config(1, 2).load().unwrap()).await.unwrap();
+    assert_eq!(report.restarted, vec![type_name::<Rigid>()]);
+
+    node.stop().await.unwrap();
+}
+
+#[tokio::test]
+async fn dependent_not_restartable() {
+    let journal = Journal::default();
+
+    let mut node = BeeNodeBuilder::<Backend>::from_config(config(1, 1).load().unwrap())
+        .unwrap()
+        .with_resource(journal.clone())
+        .with_configured_worker::<Rigid>()
+        .with_worker_cfg::<Dependent>(())
+        .finish()
+        .await
+        .unwrap();
+
+    journal.take();
+
+    assert!(matches!(
+        node.reload(config(1, 2).load().unwrap()).await,
+        Err(Error::WorkerNotRestartable(name)) if name == type_name::<Dependent>()
+    ));
+    // Nothing was stopped.
+    assert!(journal.take().is_empty());
+
+    node.stop().await.unwrap();
+}
+
+#[tokio::test]
+async fn config_watcher() {
+    let path = std::env::temp_dir().join(format!("bee-runtime-reload-{}.toml", std::process::id()));
+    std::fs::write(&path, "").unwrap();
+
+    let node = BeeNodeBuilder::<Backend>::from_config(ConfigLoader::new().load().unwrap())
+        .unwrap()
+        .with_worker_cfg::<ConfigWatcher>(ConfigWatcherConfig::new(&path).with_interval(Duration::from_millis(10)))
+        .finish()
+        .await
+        .unwrap();
+
+    let mut reloads = ReloadRequests::new(&node.bus());
+
+    sleep(Duration::from_millis(50)).await;
+    std::fs::write(&path, "[node]").unwrap();
+
+    assert_eq!(
+        timeout(Duration::from_secs(5), reloads.next()).await.unwrap(),
+        Some(ReloadRequested)
+    );
+
+    drop(reloads);
+    node.stop().await.unwrap();
+    std::fs::remove_file(&path).unwrap();
+}
@@ -0,0 +1,29 @@
+// Copyright 2020-2021 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+use bee_runtime::resource::ResourceHandle;
+
+#[test]
+fn report() {
+    let handle = ResourceHandle::new(42u32);
+
+    assert!(handle.report().handles.is_empty());
+
+    let clone = handle.clone();
+    let line = line!() - 1;
+    let weak = handle.clone().into_weak();
+    let upgraded = weak.upgrade().unwrap();
+
+    let report = clone.report();
+
+    assert_eq!(report.type_name, "u32");
+    assert_eq!(report.handles.len(), 2);
+    assert_eq!(report.handles[0].location.line(), line);
+    assert!(report.handles[0].age >= report.handles[1].age);
+
+    drop(clone);
+    drop(upgraded);
+
+    assert!(handle.report().handles.is_empty());
+    assert_eq!(handle.try_unwrap(), Some(42));
+}
@@ -0,0 +1,216 @@
+// Copyright 2020-2021 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+mod common;
+
+use common::Backend;
+
+use bee_runtime::{
+    executor::TokioExecutor,
+    node::{BeeNode, BeeNodeBuilder, BeeNodeConfig, Node, NodeBuilder},
+    schedule::{self, CronSchedule, Error, MissedTickPolicy},
+    worker::Worker,
+};
+
+use async_trait::async_trait;
+use futures::StreamExt;
+use tokio::time::{advance, sleep};
+
+use std::{
+    convert::Infallible,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    time::{Duration, UNIX_EPOCH},
+};
+
+type N = BeeNode<Backend>;
+
+struct Ticker;
+
+#[async_trait]
+impl Worker<N> for Ticker {
+    type Config = Arc<AtomicUsize>;
+    type Error = Infallible;
+
+    async fn start(node: &mut N, config: Self::Config) -> Result<Self, Self::Error> {
+        schedule::spawn_interval::<Self, _, _, _>(node, Duration::from_millis(10), MissedTickPolicy::Skip, move || {
+            let ticks = config.clone();
+            async move {
+                ticks.fetch_add(1, Ordering::SeqCst);
+            }
+        });
+
+        Ok(Self)
+    }
+}
+
+struct Delayed;
+
+#[async_trait]
+impl Worker<N> for Delayed {
+    type Config = Arc<AtomicUsize>;
+    type Error = Infallible;
+
+    async fn start(node: &mut N, config: Self::Config) -> Result<Self, Self::Error> {
+        let soon = config.clone();
+        schedule::spawn_delayed::<Self, _, _, _>(node, Duration::from_millis(10), move || async move {
+            soon.fetch_add(1, Ordering::SeqCst);
+        });
+        schedule::spawn_delayed::<Self, _, _, _>(node, Duration::from_secs(3600), move || async move {
+            config.fetch_add(100, Ordering::SeqCst);
+        });
+
+        Ok(Self)
+    }
+}
+
+struct Cron;
+
+#[async_trait]
+impl Worker<N> for Cron {
+    type Config = Arc<AtomicUsize>;
+    type Error = Infallible;
+
+    async fn start(node: &mut N, config: Self::Config) -> Result<Self, Self::Error> {
+        let schedule = "* * * * *".parse().unwrap();
+
+        schedule::spawn_cron::<Self, _, _, _>(node, schedule, MissedTickPolicy::Skip, move || {
+            let runs = config.clone();
+            async move {
+                runs.fetch_add(1, Ordering::SeqCst);
+            }
+        });
+
+        Ok(Self)
+    }
+}
+
+#[test]
+fn cron_next_after() {
+    // Friday 2021-01-08 00:00:00 UTC.
+    let friday = UNIX_EPOCH + Duration::from_secs(1_610_064_000);
+    let next = |schedule: &str, from| schedule.parse::<CronSchedule>().unwrap().next_after(from);
+
+    assert_eq!(next("* * * * *", friday), Some(friday + Duration::from_secs(60)));
+    // Monday 2021-01-11 08:00:00 UTC.
+    assert_eq!(
+        next("*/15 8-18 * * 1-5", friday + Duration::from_secs(18 * 3600 + 50 * 60)),
+        Some(UNIX_EPOCH + Duration::from_secs(1_610_352_000))
+    );
+    // Sunday 2021-01-10 12:00:00 UTC, either through its weekday or the day of the month.
+    assert_eq!(
+        next("0 12 * * 7", friday),
+        Some(UNIX_EPOCH + Duration::from_secs(1_610_280_000))
+    );
+    assert_eq!(
+        next("0 12 1 * 0", friday),
+        Some(UNIX_EPOCH + Duration::from_secs(1_610_280_000))
+    );
+    // Monday 2021-01-11 00:00:00 UTC, a stepped day of the month not restricting the days on its own.
+    assert_eq!(
+        next("0 0 */2 * 1", friday),
+        Some(UNIX_EPOCH + Duration::from_secs(1_610_323_200))
+    );
+    // Thursday 2024-02-29 00:00:00 UTC.
+    assert_eq!(
+        next("0 0 29 2 *", friday),
+        Some(UNIX_EPOCH + Duration::from_secs(1_709_164_800))
+    );
+    assert_eq!(next("0 0 31 2 *", friday), None);
+}
+
+#[test]
+fn cron_parse_errors() {
+    assert_eq!(
+        "* * * *".parse::<CronSchedule>(),
+        Err(Error::FieldCount("* * * *".to_owned()))
+    );
+    assert_eq!(
+        "60 * * * *".parse::<CronSchedule>(),
+        Err(Error::InvalidField("60".to_owned(), 0, 59))
+    );
+    assert_eq!(
+        "* 18-8 * * *".parse::<CronSchedule>(),
+        Err(Error::InvalidField("18-8".to_owned(), 0, 23))
+    );
+    assert_eq!(
+        "* * */0 * *".parse::<CronSchedule>(),
+        Err(Error::InvalidField("*/0".to_owned(), 1, 31))
+    );
+}
+
+#[tokio::test]
+async fn interval_stops_with_worker() {
+    let ticks = Arc::new(AtomicUsize::new(0));
+
+    let node = BeeNodeBuilder::<Backend>::new(BeeNodeConfig::default())
+        .unwrap()
+        .with_worker_cfg::<Ticker>(ticks.clone())
+        .finish()
+        .await
+        .unwrap();
+
+    sleep(Duration::from_millis(100)).await;
+    node.stop().await.unwrap();
+
+    let stopped = ticks.load(Ordering::SeqCst);
+    assert!(stopped > 1);
+
+    sleep(Duration::from_millis(50)).await;
+    assert_eq!(ticks.load(Ordering::SeqCst), stopped);
+}
+
+#[tokio::test]
+async fn delayed_cancelled_by_shutdown() {
+    let runs = Arc::new(AtomicUsize::new(0));
+
+    let node = BeeNodeBuilder::<Backend>::new(BeeNodeConfig::default())
+        .unwrap()
+        .with_worker_cfg::<Delayed>(runs.clone())
+        .finish()
+        .await
+        .unwrap();
+
+    sleep(Duration::from_millis(50)).await;
+    node.stop().await.unwrap();
+
+    assert_eq!(runs.load(Ordering::SeqCst), 1);
+}
+
+#[tokio::test(start_paused = true)]
+async fn cron_follows_executor_clock() {
+    let runs = Arc::new(AtomicUsize::new(0));
+
+    let node = BeeNodeBuilder::<Backend>::new(BeeNodeConfig::default())
+        .unwrap()
+        .with_worker_cfg::<Cron>(runs.clone())
+        .finish()
+        .await
+        .unwrap();
+
+    // Ten minutes of paused tokio time only take an instant of wall-clock time.
+    sleep(Duration::from_secs(10 * 60)).await;
+    node.stop().await.unwrap();
+
+    assert!((9..=10).contains(&runs.load(Ordering::SeqCst)));
+}
+
+#[tokio::test(start_paused = true)]
+async fn interval_skips_many_missed_ticks() {
+    let mut interval = schedule::Interval::new(
+        Arc::new(TokioExecutor),
+        Duration::from_micros(1),
+        MissedTickPolicy::Skip,
+    );
+    let first = interval.next().await.unwrap();
+
+    // More than `u32::MAX` periods are missed.
+    advance(Duration::from_secs(2 * 60 * 60)).await;
+    let late = interval.next().await.unwrap();
+    let now = tokio::time::Instant::now().into_std();
+
+    assert_eq!(late, first + Duration::from_micros(1));
+    assert!(interval.next().await.unwrap() >= now);
+}
@@ -0,0 +1,51 @@
+// Copyright 2020-2021 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+mod common;
+
+use common::Backend;
+
+use bee_runtime::{
+    node::{BeeNodeBuilder, BeeNodeConfig, Node, NodeBuilder},
+    signal::{ReloadRequested, Signal, SignalWorker, SignalWorkerConfig},
+};
+
+use futures::{
+    channel::{mpsc, oneshot},
+    StreamExt,
+};
+
+#[tokio::test]
+async fn reload_and_stop() {
+    let (stop_sender, stop_receiver) = oneshot::channel();
+    let (signal_sender, signal_receiver) = mpsc::unbounded();
+
+    let node = BeeNodeBuilder::<Backend>::new(BeeNodeConfig::default())
+        .unwrap()
+        .with_worker_cfg::<SignalWorker>(SignalWorkerConfig::new(stop_sender).with_signals(signal_receiver))
+        .finish()
+        .await
+        .unwrap();
+
+    let (reload_sender, mut reload_receiver) = mpsc::unbounded();
+    node.bus().add_listener::<(), ReloadRequested, _>(move |event| {
+        reload_sender.unbounded_send(*event).unwrap();
+    });
+
+    signal_sender.unbounded_send(Signal::Reload).unwrap();
+    assert_eq!(reload_receiver.next().await, Some(ReloadRequested));
+
+    signal_sender.unbounded_send(Signal::Stop).unwrap();
+    stop_receiver.await.unwrap();
+
+    node.stop().await.unwrap();
+
+    // Once the node is stopped, nothing handles the signals anymore.
+    tokio::time::timeout(std::time::Duration::from_secs(1), async {
+        while !signal_sender.is_closed() {
+            tokio::task::yield_now().await;
+        }
+    })
+    .await
+    .unwrap();
+}
@@ -0,0 +1,78 @@
+// Copyright 2020-2021 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+mod common;
+
+use common::Backend;
+
+use bee_common::logger::{span_context, span_subscriber};
+use bee_runtime::{
+    node::{BeeNode, BeeNodeBuilder, BeeNodeConfig, Node, NodeBuilder},
+    worker::Worker,
+};
+
+use async_trait::async_trait;
+
+use std::{
+    any::type_name,
+    convert::Infallible,
+    sync::{Arc, Mutex},
+};
+
+type N = BeeNode<Backend>;
+type Contexts = Arc<Mutex<Vec<Option<String>>>>;
+
+struct Traced {
+    contexts: Contexts,
+}
+
+#[async_trait]
+impl Worker<N> for Traced {
+    type Config = Contexts;
+    type Error = Infallible;
+
+    async fn start(node: &mut N, config: Self::Config) -> Result<Self, Self::Error> {
+        config.lock().unwrap().push(span_context());
+
+        let contexts = config.clone();
+        node.spawn::<Self, _, _>(|shutdown| async move {
+            contexts.lock().unwrap().push(span_context());
+            let _ = shutdown.await;
+        });
+
+        Ok(Self { contexts: config })
+    }
+
+    async fn stop(self, _node: &mut N) -> Result<(), Self::Error> {
+        self.contexts.lock().unwrap().push(span_context());
+
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn worker_spans() {
+    let _subscriber = tracing::subscriber::set_default(span_subscriber());
+    let contexts = Contexts::default();
+
+    let node = BeeNodeBuilder::<Backend>::new(BeeNodeConfig::default())
+        .unwrap()
+        .with_worker_cfg::<Traced>(contexts.clone())
+        .finish()
+        .await
+        .unwrap();
+
+    tokio::task::yield_now().await;
+    node.stop().await.unwrap();
+
+    let worker = type_name::<Traced>();
+
+    assert_eq!(
+        *contexts.lock().unwrap(),
+        vec![
+            Some(format!("start{{worker={}}}", worker)),
+            Some(format!("task{{worker={}}}", worker)),
+            Some(format!("stop{{worker={}}}", worker)),
+        ]
+    );
+}
@@ -0,0 +1,112 @@
+// Copyright 2020-2021 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+mod common;
+
+use common::Backend;
+
+use bee_runtime::{
+    node::{BeeNode, BeeNodeBuilder, BeeNodeConfig, Node, NodeBuilder},
+    status::{Health, StatusRegistry, WorkerStatus},
+    worker::{self, Worker},
+};
+
+use async_trait::async_trait;
+use tokio::time::sleep;
+
+use std::{any::type_name, convert::Infallible, fmt, time::Duration};
+
+type N = BeeNode<Backend>;
+
+#[derive(Debug)]
+struct Broken;
+
+impl fmt::Display for Broken {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "broken")
+    }
+}
+
+impl std::error::Error for Broken {}
+
+struct Healthy;
+
+#[async_trait]
+impl Worker<N> for Healthy {
+    type Config = ();
+    type Error = Infallible;
+
+    async fn start(_node: &mut N, _config: Self::Config) -> Result<Self, Self::Error> {
+        Ok(Self)
+    }
+}
+
+struct Lagging;
+
+#[async_trait]
+impl Worker<N> for Lagging {
+    type Config = ();
+    type Error = Infallible;
+
+    async fn start(_node: &mut N, _config: Self::Config) -> Result<Self, Self::Error> {
+        Ok(Self)
+    }
+
+    fn health(&self) -> Health {
+        Health::Degraded("lagging".to_owned())
+    }
+}
+
+struct Failing;
+
+#[async_trait]
+impl Worker<N> for Failing {
+    type Config = ();
+    type Error = Infallible;
+
+    async fn start(node: &mut N, _config: Self::Config) -> Result<Self, Self::Error> {
+        node.spawn_supervised::<Self, _, _>(|_shutdown| async { Err(worker::Error(Box::new(Broken))) });
+
+        Ok(Self)
+    }
+}
+
+#[tokio::test]
+async fn snapshot() {
+    let node = BeeNodeBuilder::<Backend>::new(BeeNodeConfig::default())
+        .unwrap()
+        .with_worker::<Healthy>()
+        .with_worker::<Lagging>()
+        .with_worker::<Failing>()
+        .finish()
+        .await
+        .unwrap();
+
+    sleep(Duration::from_millis(50)).await;
+
+    let statuses = StatusRegistry::clone(&node.resource::<StatusRegistry>());
+    let snapshot = statuses.snapshot();
+
+    assert_eq!(
+        snapshot.iter().map(|worker| worker.name).collect::<Vec<_>>(),
+        vec![type_name::<Healthy>(), type_name::<Lagging>(), type_name::<Failing>()]
+    );
+
+    assert_eq!(snapshot[0].status, WorkerStatus::Running);
+    assert!(snapshot[0].uptime.is_some());
+    assert_eq!(snapshot[0].health, Some(Health::Healthy));
+    assert_eq!(snapshot[0].last_error, None);
+
+    assert_eq!(snapshot[1].health, Some(Health::Degraded("lagging".to_owned())));
+
+    assert_eq!(snapshot[2].status, WorkerStatus::Failed);
+    assert!(snapshot[2].last_error.as_ref().unwrap().contains("Broken"));
+
+    node.stop().await.unwrap();
+
+    for worker in statuses.snapshot() {
+        assert_eq!(worker.status, WorkerStatus::Stopped);
+        assert_eq!(worker.uptime, None);
+        assert_eq!(worker.health, None);
+    }
+}
@@ -0,0 +1,158 @@
+// Copyright 2020-2021 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+use bee_runtime::{
+    config::ConfigLoader,
+    node::{BeeNode, BeeNodeBuilder, Node, NodeBuilder},
+    storage::StorageWorker,
+    worker::Worker,
+};
+use bee_storage::backend::StorageBackend;
+
+use async_trait::async_trait;
+use serde::Deserialize;
+use tokio::time::sleep;
+
+use std::{any::TypeId, collections::HashMap, convert::Infallible, sync::Mutex, time::Duration};
+
+static EVENTS: Mutex<Option<HashMap<String, Vec<&'static str>>>> = Mutex::new(None);
+
+fn record(name: &str, event: &'static str) {
+    EVENTS
+        .lock()
+        .unwrap()
+        .get_or_insert_with(HashMap::new)
+        .entry(name.to_owned())
+        .or_default()
+        .push(event);
+}
+
+fn events(name: &str) -> Vec<&'static str> {
+    EVENTS
+        .lock()
+        .unwrap()
+        .as_ref()
+        .and_then(|events| events.get(name).cloned())
+        .unwrap_or_default()
+}
+
+#[derive(Default, Deserialize)]
+struct BackendConfigBuilder {
+    name: Option<String>,
+}
+
+#[derive(Clone)]
+struct BackendConfig {
+    name: String,
+}
+
+impl From<BackendConfigBuilder> for BackendConfig {
+    fn from(builder: BackendConfigBuilder) -> Self {
+        Self {
+            name: builder.name.unwrap_or_default(),
+        }
+    }
+}
+
+struct Backend {
+    name: String,
+}
+
+#[async_trait]
+impl StorageBackend for Backend {
+    type ConfigBuilder = BackendConfigBuilder;
+    type Config = BackendConfig;
+    type Error = Infallible;
+
+    async fn start(config: Self::Config) -> Result<Self, Self::Error> {
+        record(&config.name, "backend start");
+        Ok(Self { name: config.name })
+    }
+
+    async fn shutdown(self) -> Result<(), Self::Error> {
+        record(&self.name, "backend shutdown");
+        Ok(())
+    }
+
+    async fn size(&self) -> Result<Option<usize>, Self::Error> {
+        Ok(None)
+    }
+}
+
+type N = BeeNode<Backend>;
+
+struct User {
+    name: String,
+}
+
+#[async_trait]
+impl Worker<N> for User {
+    type Config = ();
+    type Error = Infallible;
+
+    fn dependencies() -> &'static [TypeId] {
+        Box::leak(Box::from(vec![TypeId::of::<StorageWorker<Backend>>()]))
+    }
+
+    async fn start(node: &mut N, _config: Self::Config) -> Result<Self, Self::Error> {
+        let name = node.storage().name.clone();
+
+        record(&name, "user start");
+        Ok(Self { name })
+    }
+
+    async fn stop(self, _node: &mut N) -> Result<(), Self::Error> {
+        record(&self.name, "user stop");
+        Ok(())
+    }
+}
+
+async fn node(name: &str) -> N {
+    let config = ConfigLoader::new().set("workers.storage.name", name).load().unwrap();
+
+    BeeNodeBuilder::<Backend>::from_config(config)
+        .unwrap()
+        .with_worker::<User>()
+        .with_configured_worker::<StorageWorker<Backend>>()
+        .finish()
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn backend_lifecycle() {
+    let node = node("lifecycle").await;
+
+    assert_eq!(events("lifecycle"), vec!["backend start", "user start"]);
+
+    node.stop().await.unwrap();
+
+    assert_eq!(
+        events("lifecycle"),
+        vec!["backend start", "user start", "user stop", "backend shutdown"]
+    );
+}
+
+#[tokio::test]
+async fn leaked_handle_blocks_shutdown() {
+    let node = node("leaked").await;
+    let storage = node.storage();
+
+    let error = node.stop().await.unwrap_err().to_string();
+
+    assert!(error.contains("InUse"), "{}", error);
+    assert!(error.contains("tests/storage.rs"), "{}", error);
+    // Only the leaked handle is reported, not the one of the storage worker.
+    assert_eq!(error.matches("HandleReport").count(), 1, "{}", error);
+    assert_eq!(events("leaked"), vec!["backend start", "user start", "user stop"]);
+    assert_eq!(storage.name, "leaked");
+
+    // The backend is shut down once the leaked handle is dropped.
+    drop(storage);
+    sleep(Duration::from_millis(500)).await;
+
+    assert_eq!(
+        events("leaked"),
+        vec!["backend start", "user start", "user stop", "backend shutdown"]
+    );
+}
@@ -0,0 +1,185 @@
+// Copyright 2020-2021 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+mod common;
+
+use common::Backend;
+
+use bee_runtime::{
+    event::Bus,
+    node::{BeeNode, BeeNodeBuilder, BeeNodeConfig, Node, NodeBuilder},
+    supervisor::{RestartPolicy, TaskPanicked, WorkerFailed},
+    worker::{self, Worker},
+};
+
+use async_trait::async_trait;
+use tokio::time::sleep;
+
+use std::{
+    convert::Infallible,
+    fmt,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+    time::Duration,
+};
+
+type N = BeeNode<Backend>;
+
+#[derive(Debug)]
+struct Flaky;
+
+impl fmt::Display for Flaky {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "flaky")
+    }
+}
+
+impl std::error::Error for Flaky {}
+
+/// A worker whose task fails the given number of times before running until shut down.
+struct Failing;
+
+#[async_trait]
+impl Worker<N> for Failing {
+    type Config = usize;
+    type Error = Infallible;
+
+    async fn start(node: &mut N, failures: Self::Config) -> Result<Self, Self::Error> {
+        let attempts = node.resource::<Arc<AtomicUsize>>();
+
+        node.spawn_supervised::<Self, _, _>(move |shutdown| {
+            let attempt = attempts.fetch_add(1, Ordering::SeqCst);
+
+            async move {
+                if attempt < failures {
+                    return Err(worker::Error(Box::new(Flaky)));
+                }
+                let _ = shutdown.await;
+                Ok(())
+            }
+        });
+
+        Ok(Self)
+    }
+}
+
+struct Panicking;
+
+#[async_trait]
+impl Worker<N> for Panicking {
+    type Config = ();
+    type Error = Infallible;
+
+    async fn start(node: &mut N, _config: Self::Config) -> Result<Self, Self::Error> {
+        node.spawn_supervised::<Self, _, _>(|_shutdown| async { panic!("oops") });
+
+        Ok(Self)
+    }
+}
+
+type Failures = Arc<Mutex<Vec<(bool, usize, Option<Duration>)>>>;
+
+fn record_failures(bus: &Bus<'static>) -> Failures {
+    let failures = Failures::default();
+    let failures_clone = failures.clone();
+
+    bus.add_listener::<(), WorkerFailed, _>(move |event| {
+        failures_clone.lock().unwrap().push((
+            event
+                .error
+                .0
+                .downcast_ref::<TaskPanicked>()
+                .is_some_and(|e| e.0 == "oops"),
+            event.restarts,
+            event.backoff,
+        ));
+    });
+
+    failures
+}
+
+fn policy() -> RestartPolicy {
+    RestartPolicy::on_failure().with_backoff(Duration::from_millis(1), Duration::from_millis(2))
+}
+
+#[tokio::test]
+async fn restart_on_failure() {
+    let attempts = Arc::new(AtomicUsize::new(0));
+
+    let bus = Bus::default();
+    let failures = record_failures(&bus);
+
+    let node = BeeNodeBuilder::<Backend>::new(BeeNodeConfig::default())
+        .unwrap()
+        .with_resource(bus)
+        .with_resource(attempts.clone())
+        .with_worker_cfg::<Failing>(3)
+        .with_restart_policy::<Failing>(policy())
+        .finish()
+        .await
+        .unwrap();
+
+    sleep(Duration::from_millis(100)).await;
+
+    assert_eq!(attempts.load(Ordering::SeqCst), 4);
+    assert_eq!(
+        *failures.lock().unwrap(),
+        vec![
+            (false, 0, Some(Duration::from_millis(1))),
+            (false, 1, Some(Duration::from_millis(2))),
+            (false, 2, Some(Duration::from_millis(2))),
+        ]
+    );
+
+    node.stop().await.unwrap();
+}
+
+#[tokio::test]
+async fn max_restarts() {
+    let attempts = Arc::new(AtomicUsize::new(0));
+
+    let bus = Bus::default();
+    let failures = record_failures(&bus);
+
+    let node = BeeNodeBuilder::<Backend>::new(BeeNodeConfig::default())
+        .unwrap()
+        .with_resource(bus)
+        .with_resource(attempts.clone())
+        .with_worker_cfg::<Failing>(usize::MAX)
+        .with_restart_policy::<Failing>(policy().with_max_restarts(1))
+        .finish()
+        .await
+        .unwrap();
+
+    sleep(Duration::from_millis(100)).await;
+
+    assert_eq!(attempts.load(Ordering::SeqCst), 2);
+    assert_eq!(
+        *failures.lock().unwrap(),
+        vec![(false, 0, Some(Duration::from_millis(1))), (false, 1, None)]
+    );
+
+    node.stop().await.unwrap();
+}
+
+#[tokio::test]
+async fn panic_is_reported() {
+    let bus = Bus::default();
+    let failures = record_failures(&bus);
+
+    let node = BeeNodeBuilder::<Backend>::new(BeeNodeConfig::default())
+        .unwrap()
+        .with_resource(bus)
+        .with_worker::<Panicking>()
+        .finish()
+        .await
+        .unwrap();
+
+    sleep(Duration::from_millis(100)).await;
+
+    assert_eq!(*failures.lock().unwrap(), vec![(true, 0, None)]);
+
+    node.stop().await.unwrap();
+}
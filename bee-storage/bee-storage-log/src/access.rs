@@ -0,0 +1,339 @@
+// Copyright 2020-2021 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    backend::{Error, LogBackend},
+    record::Operation,
+    store::{Index, Location},
+};
+
+use bee_common::packable::Packable;
+use bee_storage::access::{
+    AsKeyStream, AsPrefixStream, AsRangeStream, AsReverseStream, AsStream, Batch, BatchBuilder, Delete, Exist, Fetch,
+    Insert, Keyspace, Truncate,
+};
+
+use async_trait::async_trait;
+use futures::stream::{self, Iter};
+
+use std::{
+    any::type_name,
+    ops::Bound::{Excluded, Included, Unbounded},
+    vec::IntoIter,
+};
+
+/// The identifier of the keyspace of a `(K, V)` pair in the log, a FNV-1a hash of its `Keyspace` name.
+fn keyspace<S: Keyspace<K, V>, K, V>() -> u64 {
+    S::NAME.bytes().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+fn unpack<P: Packable>(mut bytes: &[u8]) -> Result<P, Error> {
+    P::unpack(&mut bytes).map_err(|e| Error::Unpack(type_name::<P>(), format!("{:?}", e)))
+}
+
+/// Copy the keys and locations of entries out of the index, that can't be borrowed while reading the log.
+fn locations<'k>(entries: impl Iterator<Item = (&'k Vec<u8>, &'k Location)>) -> Vec<(Vec<u8>, Location)> {
+    entries.map(|(key, location)| (key.clone(), *location)).collect()
+}
+
+/// Read the entries of the keyspace selected by `select` on the I/O thread, and unpack them into a stream.
+async fn snapshot<S, K, V, F>(
+    backend: &LogBackend<S>,
+    keyspace: u64,
+    select: F,
+) -> Result<Iter<IntoIter<(K, V)>>, Error>
+where
+    K: Packable,
+    V: Packable,
+    F: FnOnce(&Index) -> Vec<(Vec<u8>, Location)> + Send + 'static,
+{
+    let entries = backend
+        .with_store(move |store| {
+            store
+                .index(keyspace)
+                .map(select)
+                .unwrap_or_default()
+                .into_iter()
+                .map(|(key, location)| Ok((key, store.read(location)?)))
+                .collect::<Result<Vec<_>, Error>>()
+        })
+        .await?
+        .into_iter()
+        .map(|(key, value)| Ok((unpack(&key)?, unpack(&value)?)))
+        .collect::<Result<Vec<_>, Error>>()?;
+
+    Ok(stream::iter(entries))
+}
+
+/// A batch of write operations, appended to the log as a single record by [`BatchBuilder::batch_commit`].
+#[derive(Default)]
+pub struct LogBatch {
+    operations: Vec<Operation>,
+}
+
+#[async_trait]
+impl<S, K, V> Fetch<K, V> for LogBackend<S>
+where
+    S: Keyspace<K, V> + 'static,
+    K: Packable + Send + Sync,
+    V: Packable + Send + Sync,
+{
+    async fn fetch(&self, key: &K) -> Result<Option<V>, Self::Error> {
+        let (keyspace, key) = (keyspace::<S, K, V>(), key.pack_new());
+        let value = self
+            .with_store(move |store| match store.get(keyspace, &key) {
+                Some(location) => Ok(Some(store.read(location)?)),
+                None => Ok(None),
+            })
+            .await?;
+
+        value.map(|value| unpack(&value)).transpose()
+    }
+
+    /// Fetches all the values in a single operation of the I/O thread, such that they are consistent with each other.
+    async fn multi_fetch<'k, I>(&self, keys: I) -> Result<Vec<Option<V>>, Self::Error>
+    where
+        K: 'k,
+        I: IntoIterator<Item = &'k K> + Send,
+        I::IntoIter: Send,
+    {
+        let keyspace = keyspace::<S, K, V>();
+        let keys = keys.into_iter().map(|key| key.pack_new()).collect::<Vec<_>>();
+        let values = self
+            .with_store(move |store| {
+                keys.iter()
+                    .map(|key| match store.get(keyspace, key) {
+                        Some(location) => Ok(Some(store.read(location)?)),
+                        None => Ok(None),
+                    })
+                    .collect::<Result<Vec<_>, Error>>()
+            })
+            .await?;
+
+        values
+            .into_iter()
+            .map(|value| value.map(|value| unpack(&value)).transpose())
+            .collect()
+    }
+}
+
+#[async_trait]
+impl<S, K, V> Exist<K, V> for LogBackend<S>
+where
+    S: Keyspace<K, V> + 'static,
+    K: Packable + Send + Sync,
+    V: Packable + Send + Sync,
+{
+    /// Only looks the key up in the index, without reading the log.
+    async fn exist(&self, key: &K) -> Result<bool, Self::Error> {
+        let (keyspace, key) = (keyspace::<S, K, V>(), key.pack_new());
+
+        self.with_store(move |store| Ok(store.get(keyspace, &key).is_some()))
+            .await
+    }
+}
+
+#[async_trait]
+impl<S, K, V> Insert<K, V> for LogBackend<S>
+where
+    S: Keyspace<K, V> + 'static,
+    K: Packable + Send + Sync,
+    V: Packable + Send + Sync,
+{
+    async fn insert(&self, key: &K, value: &V) -> Result<(), Self::Error> {
+        let operation = Operation::Insert {
+            keyspace: keyspace::<S, K, V>(),
+            key: key.pack_new(),
+            value: value.pack_new(),
+        };
+
+        self.with_store(move |store| Ok(store.append(&[operation], false)?))
+            .await
+    }
+}
+
+#[async_trait]
+impl<S, K, V> Delete<K, V> for LogBackend<S>
+where
+    S: Keyspace<K, V> + 'static,
+    K: Packable + Send + Sync,
+    V: Packable + Send + Sync,
+{
+    async fn delete(&self, key: &K) -> Result<(), Self::Error> {
+        let operation = Operation::Delete {
+            keyspace: keyspace::<S, K, V>(),
+            key: key.pack_new(),
+        };
+
+        self.with_store(move |store| Ok(store.append(&[operation], false)?))
+            .await
+    }
+}
+
+#[async_trait]
+impl<S, K, V> Truncate<K, V> for LogBackend<S>
+where
+    S: Keyspace<K, V> + 'static,
+    K: Packable + Send + Sync,
+    V: Packable + Send + Sync,
+{
+    async fn truncate(&self) -> Result<(), Self::Error> {
+        let operation = Operation::Truncate {
+            keyspace: keyspace::<S, K, V>(),
+        };
+
+        self.with_store(move |store| Ok(store.append(&[operation], false)?))
+            .await
+    }
+}
+
+#[async_trait]
+impl<'a, S, K, V> AsStream<'a, K, V> for LogBackend<S>
+where
+    S: Keyspace<K, V> + 'static,
+    K: Packable + Send + Sync,
+    V: Packable + Send + Sync,
+{
+    type Stream = Iter<IntoIter<(K, V)>>;
+
+    /// Streams a snapshot of the keyspace, ordered by packed key, taken when the stream is created.
+    async fn stream(&'a self) -> Result<Self::Stream, Self::Error> {
+        snapshot(self, keyspace::<S, K, V>(), |index| locations(index.iter())).await
+    }
+}
+
+#[async_trait]
+impl<'a, S, K, V> AsRangeStream<'a, K, V> for LogBackend<S>
+where
+    S: Keyspace<K, V> + 'static,
+    K: Packable + Send + Sync,
+    V: Packable + Send + Sync,
+{
+    type RangeStream = Iter<IntoIter<(K, V)>>;
+
+    /// Streams a snapshot of the range of the keyspace, taken when the stream is created.
+    async fn range_stream(&'a self, from: &K, to: &K) -> Result<Self::RangeStream, Self::Error> {
+        let (from, to) = (from.pack_new(), to.pack_new());
+
+        // A range whose start is after its end is empty, but not a valid range of a map.
+        if from >= to {
+            return Ok(stream::iter(Vec::new()));
+        }
+
+        snapshot(self, keyspace::<S, K, V>(), move |index| {
+            locations(index.range::<[u8], _>((Included(from.as_slice()), Excluded(to.as_slice()))))
+        })
+        .await
+    }
+}
+
+#[async_trait]
+impl<'a, S, K, V> AsPrefixStream<'a, K, V> for LogBackend<S>
+where
+    S: Keyspace<K, V> + 'static,
+    K: Packable + Send + Sync,
+    V: Packable + Send + Sync,
+{
+    type PrefixStream = Iter<IntoIter<(K, V)>>;
+
+    /// Streams a snapshot of the keys of the keyspace with the prefix, taken when the stream is created.
+    async fn prefix_stream(&'a self, prefix: &[u8]) -> Result<Self::PrefixStream, Self::Error> {
+        let prefix = prefix.to_vec();
+
+        snapshot(self, keyspace::<S, K, V>(), move |index| {
+            locations(
+                index
+                    .range::<[u8], _>((Included(prefix.as_slice()), Unbounded))
+                    .take_while(|(key, _)| key.starts_with(&prefix)),
+            )
+        })
+        .await
+    }
+}
+
+#[async_trait]
+impl<'a, S, K, V> AsReverseStream<'a, K, V> for LogBackend<S>
+where
+    S: Keyspace<K, V> + 'static,
+    K: Packable + Send + Sync,
+    V: Packable + Send + Sync,
+{
+    type ReverseStream = Iter<IntoIter<(K, V)>>;
+
+    /// Streams a snapshot of the keyspace, in reverse order, taken when the stream is created.
+    async fn reverse_stream(&'a self) -> Result<Self::ReverseStream, Self::Error> {
+        snapshot(self, keyspace::<S, K, V>(), |index| locations(index.iter().rev())).await
+    }
+}
+
+#[async_trait]
+impl<'a, S, K, V> AsKeyStream<'a, K, V> for LogBackend<S>
+where
+    S: Keyspace<K, V> + 'static,
+    K: Packable + Send + Sync,
+    V: Packable + Send + Sync,
+{
+    type KeyStream = Iter<IntoIter<K>>;
+
+    /// Streams a snapshot of the keys of the keyspace, taken from the index when the stream is created.
+    async fn key_stream(&'a self) -> Result<Self::KeyStream, Self::Error> {
+        let keyspace = keyspace::<S, K, V>();
+        let keys = self
+            .with_store(move |store| {
+                Ok(store
+                    .index(keyspace)
+                    .map(|index| index.keys().cloned().collect::<Vec<_>>())
+                    .unwrap_or_default())
+            })
+            .await?
+            .iter()
+            .map(|key| unpack(key))
+            .collect::<Result<Vec<_>, Error>>()?;
+
+        Ok(stream::iter(keys))
+    }
+}
+
+#[async_trait]
+impl<S: 'static> BatchBuilder for LogBackend<S> {
+    type Batch = LogBatch;
+
+    /// Appends all the operations of the batch as a single record, that is recovered entirely or not at all, and
+    /// flushes it to the disk before returning if `durability` is set.
+    async fn batch_commit(&self, batch: Self::Batch, durability: bool) -> Result<(), Self::Error> {
+        if batch.operations.is_empty() {
+            return Ok(());
+        }
+
+        self.with_store(move |store| Ok(store.append(&batch.operations, durability)?))
+            .await
+    }
+}
+
+impl<S, K, V> Batch<K, V> for LogBackend<S>
+where
+    S: Keyspace<K, V> + 'static,
+    K: Packable + Send + Sync,
+    V: Packable + Send + Sync,
+{
+    fn batch_insert(&self, batch: &mut Self::Batch, key: &K, value: &V) -> Result<(), Self::Error> {
+        batch.operations.push(Operation::Insert {
+            keyspace: keyspace::<S, K, V>(),
+            key: key.pack_new(),
+            value: value.pack_new(),
+        });
+
+        Ok(())
+    }
+
+    fn batch_delete(&self, batch: &mut Self::Batch, key: &K) -> Result<(), Self::Error> {
+        batch.operations.push(Operation::Delete {
+            keyspace: keyspace::<S, K, V>(),
+            key: key.pack_new(),
+        });
+
+        Ok(())
+    }
+}
@@ -0,0 +1,219 @@
+// Copyright 2020-2021 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    config::{LogConfig, LogConfigBuilder},
+    store::Store,
+};
+
+use bee_storage::backend::StorageBackend;
+
+use async_trait::async_trait;
+use futures::channel::oneshot;
+use log::{error, info};
+use thiserror::Error;
+
+use std::{
+    io,
+    marker::PhantomData,
+    sync::{
+        mpsc::{self, RecvTimeoutError},
+        Arc, Mutex,
+    },
+    thread::{self, JoinHandle},
+};
+
+const IO_THREAD: &str = "bee-storage-log-io";
+const COMPACTOR_THREAD: &str = "bee-storage-log-compactor";
+
+/// Errors that may occur on log backend operations.
+#[derive(Error, Debug)]
+pub enum Error {
+    /// Reading or writing the log failed.
+    #[error("I/O error: {0}.")]
+    Io(#[from] io::Error),
+    /// A stored key or value could not be unpacked into its type.
+    #[error("Unpacking `{0}` failed: {1}.")]
+    Unpack(&'static str, String),
+    /// A record of the log is corrupted, and is not a record torn at its end by a crash.
+    #[error("Corrupted record at offset {0} of the log, followed by {1} bytes.")]
+    Corrupted(u64, u64),
+    /// A thread of the backend stopped unexpectedly.
+    #[error("The `{0}` thread of the log backend stopped.")]
+    ThreadStopped(&'static str),
+}
+
+/// A file operation, run on the I/O thread of the backend.
+type Job = Box<dyn FnOnce(&Mutex<Store>) + Send>;
+
+/// A request to compact the log right away, along with the sender of its result.
+type CompactionRequest = oneshot::Sender<Result<(), Error>>;
+
+/// A persistent storage backend that appends every write to a log file and keeps the positions of the live entries in
+/// an in-memory index.
+///
+/// The entries of each `(K, V)` pair are stored in the keyspace named by the `Keyspace<K, V>` implementation of `S`.
+///
+/// Writes are flushed to the operating system as they happen, and to the disk when a batch is committed with
+/// `durability`. The file operations run on a dedicated I/O thread, such that they never block the async tasks, and a
+/// background thread compacts the log once enough of it is made of overwritten or deleted entries.
+pub struct LogBackend<S> {
+    jobs: mpsc::Sender<Job>,
+    io: JoinHandle<()>,
+    compactor: (mpsc::Sender<CompactionRequest>, JoinHandle<()>),
+    marker: PhantomData<fn() -> S>,
+}
+
+impl<S> LogBackend<S> {
+    /// Run `f` with the store on the I/O thread.
+    pub(crate) async fn with_store<T, F>(&self, f: F) -> Result<T, Error>
+    where
+        T: Send + 'static,
+        F: FnOnce(&mut Store) -> Result<T, Error> + Send + 'static,
+    {
+        run(&self.jobs, move |store| f(&mut store.lock().unwrap())).await
+    }
+
+    /// Compact the log right away, rewriting it with its live entries only.
+    pub async fn compact(&self) -> Result<(), Error> {
+        let (sender, receiver) = oneshot::channel();
+
+        self.compactor
+            .0
+            .send(sender)
+            .map_err(|_| Error::ThreadStopped(COMPACTOR_THREAD))?;
+
+        receiver.await.map_err(|_| Error::ThreadStopped(COMPACTOR_THREAD))?
+    }
+}
+
+/// Run `f` on the I/O thread, waiting for its result without blocking.
+async fn run<T, F>(jobs: &mpsc::Sender<Job>, f: F) -> Result<T, Error>
+where
+    T: Send + 'static,
+    F: FnOnce(&Mutex<Store>) -> Result<T, Error> + Send + 'static,
+{
+    let (sender, receiver) = oneshot::channel();
+
+    jobs.send(Box::new(move |store| {
+        let _ = sender.send(f(store));
+    }))
+    .map_err(|_| Error::ThreadStopped(IO_THREAD))?;
+
+    receiver.await.map_err(|_| Error::ThreadStopped(IO_THREAD))?
+}
+
+/// Rewrite the log with its live entries only, only holding the store to take a snapshot of its index and to replace
+/// it, returning its length before and after the compaction.
+fn compact(store: &Mutex<Store>) -> Result<(u64, u64), Error> {
+    let compaction = store.lock().unwrap().compaction()?;
+    let compacted = compaction.copy()?;
+    let mut store = store.lock().unwrap();
+    let before = store.len();
+
+    store.finish_compaction(compacted)?;
+
+    Ok((before, store.len()))
+}
+
+fn compactor(store: &Mutex<Store>, config: &LogConfig, requests: mpsc::Receiver<CompactionRequest>) {
+    loop {
+        match requests.recv_timeout(config.compaction_interval) {
+            Ok(request) => {
+                let _ = request.send(compact(store).map(|_| ()));
+            }
+            Err(RecvTimeoutError::Timeout) => {
+                if !store
+                    .lock()
+                    .unwrap()
+                    .should_compact(config.compaction_ratio, config.compaction_min_size)
+                {
+                    continue;
+                }
+
+                match compact(store) {
+                    Ok((before, after)) => info!("Compacted storage log from {} to {} bytes.", before, after),
+                    Err(e) => error!("Compacting storage log failed: {}.", e),
+                }
+            }
+            Err(RecvTimeoutError::Disconnected) => break,
+        }
+    }
+}
+
+#[async_trait]
+impl<S: 'static> StorageBackend for LogBackend<S> {
+    type ConfigBuilder = LogConfigBuilder;
+    type Config = LogConfig;
+    type Error = Error;
+
+    async fn start(config: Self::Config) -> Result<Self, Self::Error> {
+        let (jobs, pending) = mpsc::channel::<Job>();
+        let (opened, opening) = oneshot::channel();
+
+        // Opening the store replays the whole log, that is also done on the I/O thread.
+        let io = thread::Builder::new().name(IO_THREAD.to_owned()).spawn({
+            let path = config.path.clone();
+
+            move || {
+                let store = match Store::open(&path) {
+                    Ok(store) => Arc::new(Mutex::new(store)),
+                    Err(e) => {
+                        let _ = opened.send(Err(e));
+                        return;
+                    }
+                };
+
+                let _ = opened.send(Ok(store.clone()));
+                for job in pending {
+                    job(&store);
+                }
+            }
+        })?;
+        let store = opening.await.map_err(|_| Error::ThreadStopped(IO_THREAD))??;
+
+        let (requests, received) = mpsc::channel();
+        let compactor = thread::Builder::new().name(COMPACTOR_THREAD.to_owned()).spawn({
+            let store = store.clone();
+
+            move || compactor(&store, &config, received)
+        })?;
+
+        Ok(Self {
+            jobs,
+            io,
+            compactor: (requests, compactor),
+            marker: PhantomData,
+        })
+    }
+
+    /// Waits for a compaction in progress, if any, and flushes the log to the disk.
+    async fn shutdown(self) -> Result<(), Self::Error> {
+        let Self {
+            jobs,
+            io,
+            compactor: (requests, compactor),
+            ..
+        } = self;
+
+        // The compactor is stopped from the I/O thread, as it may have to wait for the end of a compaction.
+        let synced = run(&jobs, move |store| {
+            drop(requests);
+            // A compactor that panicked has nothing left to clean up.
+            let _ = compactor.join();
+
+            Ok(store.lock().unwrap().sync()?)
+        })
+        .await;
+
+        drop(jobs);
+        let _ = io.join();
+
+        synced
+    }
+
+    /// The size of the log file, dead entries included until they are compacted.
+    async fn size(&self) -> Result<Option<usize>, Self::Error> {
+        self.with_store(|store| Ok(Some(store.len() as usize))).await
+    }
+}
@@ -0,0 +1,90 @@
+// Copyright 2020-2021 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+use serde::Deserialize;
+
+use std::{path::PathBuf, time::Duration};
+
+const DEFAULT_PATH: &str = "./storage";
+const DEFAULT_COMPACTION_RATIO: f64 = 0.5;
+const DEFAULT_COMPACTION_MIN_SIZE: u64 = 1024 * 1024;
+const DEFAULT_COMPACTION_INTERVAL_MS: u64 = 60_000;
+
+/// Builder for a [`LogConfig`].
+#[derive(Default, Deserialize)]
+pub struct LogConfigBuilder {
+    path: Option<PathBuf>,
+    compaction_ratio: Option<f64>,
+    compaction_min_size: Option<u64>,
+    compaction_interval_ms: Option<u64>,
+}
+
+impl LogConfigBuilder {
+    /// Creates a new builder for a log backend configuration.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the directory that holds the log file.
+    pub fn path<P: Into<PathBuf>>(mut self, path: P) -> Self {
+        self.path.replace(path.into());
+        self
+    }
+
+    /// Sets the ratio of the log, made of overwritten or deleted entries, above which it is compacted.
+    pub fn compaction_ratio(mut self, ratio: f64) -> Self {
+        self.compaction_ratio.replace(ratio);
+        self
+    }
+
+    /// Sets the size, in bytes, below which the log is never compacted.
+    pub fn compaction_min_size(mut self, size: u64) -> Self {
+        self.compaction_min_size.replace(size);
+        self
+    }
+
+    /// Sets the time between two checks of whether the log should be compacted.
+    pub fn compaction_interval(mut self, interval: Duration) -> Self {
+        self.compaction_interval_ms.replace(interval.as_millis() as u64);
+        self
+    }
+
+    /// Builds a log backend configuration.
+    pub fn finish(self) -> LogConfig {
+        LogConfig {
+            path: self.path.unwrap_or_else(|| PathBuf::from(DEFAULT_PATH)),
+            compaction_ratio: self.compaction_ratio.unwrap_or(DEFAULT_COMPACTION_RATIO),
+            compaction_min_size: self.compaction_min_size.unwrap_or(DEFAULT_COMPACTION_MIN_SIZE),
+            compaction_interval: Duration::from_millis(
+                self.compaction_interval_ms.unwrap_or(DEFAULT_COMPACTION_INTERVAL_MS),
+            ),
+        }
+    }
+}
+
+impl From<LogConfigBuilder> for LogConfig {
+    fn from(builder: LogConfigBuilder) -> Self {
+        builder.finish()
+    }
+}
+
+/// Configuration of a [`LogBackend`](crate::LogBackend).
+#[derive(Clone, Debug)]
+pub struct LogConfig {
+    pub(crate) path: PathBuf,
+    pub(crate) compaction_ratio: f64,
+    pub(crate) compaction_min_size: u64,
+    pub(crate) compaction_interval: Duration,
+}
+
+impl LogConfig {
+    /// Creates a new builder for a log backend configuration.
+    pub fn build() -> LogConfigBuilder {
+        LogConfigBuilder::new()
+    }
+
+    /// Returns the directory that holds the log file.
+    pub fn path(&self) -> &PathBuf {
+        &self.path
+    }
+}
@@ -0,0 +1,35 @@
+// Copyright 2020-2021 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+//! A persistent log-structured storage backend for the bee framework, written in pure Rust.
+//!
+//! # Features
+//!
+//! - Implementation of every access trait of `bee-storage`, for any `Packable` key and value types;
+//! - Append-only log file, with an in-memory index of the position of the live values;
+//! - Atomic batches, flushed to the disk on commit when durability is requested;
+//! - Crash recovery, truncating the torn record at the end of the log and refusing to open a corrupted one;
+//! - File operations on a dedicated I/O thread, off the async tasks;
+//! - Background compaction of the overwritten and deleted entries, that only blocks the other operations while it
+//!   replaces the log;
+//!
+//! Each `(K, V)` pair has its own keyspace, identified by its explicit `Keyspace` name: renaming a keyspace makes its
+//! previous entries unreachable.
+
+#![deny(missing_docs)]
+#![deny(warnings)]
+
+/// Access module which implements the access traits of `bee-storage` for the log backend.
+mod access;
+/// Backend module which holds the log backend itself and implements `StorageBackend` for it.
+mod backend;
+/// Config module which holds the configuration of the log backend and its builder.
+mod config;
+/// Record module which holds the format of the records of the log.
+mod record;
+/// Store module which holds the log file and its index.
+mod store;
+
+pub use access::LogBatch;
+pub use backend::{Error, LogBackend};
+pub use config::{LogConfig, LogConfigBuilder};
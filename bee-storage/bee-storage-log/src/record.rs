@@ -0,0 +1,149 @@
+// Copyright 2020-2021 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+//! The format of the records appended to the log.
+//!
+//! A record is made of a header, holding the length and the CRC32 checksum of its payload along with the CRC32 checksum
+//! of these two fields, followed by the payload itself, a sequence of operations that are applied all together or not
+//! at all:
+//! ```text
+//! record    = payload length (u32) | payload checksum (u32) | header checksum (u32) | operation*
+//! operation = 0 | keyspace (u64) | key length (u32) | key | value length (u32) | value  (insert)
+//!           | 1 | keyspace (u64) | key length (u32) | key                                (delete)
+//!           | 2 | keyspace (u64)                                                         (truncate)
+//! ```
+//! Integers are little-endian.
+
+use std::convert::TryInto;
+
+pub(crate) const HEADER_LEN: usize = 12;
+
+const INSERT: u8 = 0;
+const DELETE: u8 = 1;
+const TRUNCATE: u8 = 2;
+
+pub(crate) enum Operation {
+    Insert {
+        keyspace: u64,
+        key: Vec<u8>,
+        value: Vec<u8>,
+    },
+    Delete {
+        keyspace: u64,
+        key: Vec<u8>,
+    },
+    Truncate {
+        keyspace: u64,
+    },
+}
+
+/// An operation decoded from a payload, borrowing its key, with the position of its value in the payload.
+pub(crate) enum Decoded<'a> {
+    Insert {
+        keyspace: u64,
+        key: &'a [u8],
+        value: (usize, u32),
+    },
+    Delete {
+        keyspace: u64,
+        key: &'a [u8],
+    },
+    Truncate {
+        keyspace: u64,
+    },
+}
+
+/// The length of the record of a single insert operation.
+pub(crate) fn insert_len(key_len: usize, value_len: u32) -> u64 {
+    (HEADER_LEN + 1 + 8 + 4 + key_len + 4) as u64 + value_len as u64
+}
+
+pub(crate) fn encode(operations: &[Operation]) -> Vec<u8> {
+    let mut payload = Vec::new();
+
+    for operation in operations {
+        match operation {
+            Operation::Insert { keyspace, key, value } => {
+                payload.push(INSERT);
+                payload.extend_from_slice(&keyspace.to_le_bytes());
+                encode_bytes(&mut payload, key);
+                encode_bytes(&mut payload, value);
+            }
+            Operation::Delete { keyspace, key } => {
+                payload.push(DELETE);
+                payload.extend_from_slice(&keyspace.to_le_bytes());
+                encode_bytes(&mut payload, key);
+            }
+            Operation::Truncate { keyspace } => {
+                payload.push(TRUNCATE);
+                payload.extend_from_slice(&keyspace.to_le_bytes());
+            }
+        }
+    }
+
+    let mut record = Vec::with_capacity(HEADER_LEN + payload.len());
+    record.extend_from_slice(&(payload.len() as u32).to_le_bytes());
+    record.extend_from_slice(&crc32fast::hash(&payload).to_le_bytes());
+    record.extend_from_slice(&crc32fast::hash(&record).to_le_bytes());
+    record.extend_from_slice(&payload);
+    record
+}
+
+fn encode_bytes(payload: &mut Vec<u8>, bytes: &[u8]) {
+    payload.extend_from_slice(&(bytes.len() as u32).to_le_bytes());
+    payload.extend_from_slice(bytes);
+}
+
+/// Split a header into the length and the checksum of the payload, or `None` if it doesn't match its own checksum.
+pub(crate) fn decode_header(header: &[u8; HEADER_LEN]) -> Option<(usize, u32)> {
+    if crc32fast::hash(&header[..8]) != u32::from_le_bytes([header[8], header[9], header[10], header[11]]) {
+        return None;
+    }
+
+    Some((
+        u32::from_le_bytes([header[0], header[1], header[2], header[3]]) as usize,
+        u32::from_le_bytes([header[4], header[5], header[6], header[7]]),
+    ))
+}
+
+/// Decode the operations of a payload, or `None` if it is malformed.
+pub(crate) fn decode(payload: &[u8]) -> Option<Vec<Decoded<'_>>> {
+    let mut operations = Vec::new();
+    let mut position = 0;
+
+    while position < payload.len() {
+        let kind = payload[position];
+        let keyspace = u64::from_le_bytes(payload.get(position + 1..position + 9)?.try_into().ok()?);
+        position += 9;
+
+        operations.push(match kind {
+            INSERT => {
+                let key = decode_bytes(payload, &mut position)?;
+                let value_start = position + 4;
+                let value = decode_bytes(payload, &mut position)?;
+
+                Decoded::Insert {
+                    keyspace,
+                    key,
+                    value: (value_start, value.len() as u32),
+                }
+            }
+            DELETE => Decoded::Delete {
+                keyspace,
+                key: decode_bytes(payload, &mut position)?,
+            },
+            TRUNCATE => Decoded::Truncate { keyspace },
+            _ => return None,
+        });
+    }
+
+    Some(operations)
+}
+
+fn decode_bytes<'a>(payload: &'a [u8], position: &mut usize) -> Option<&'a [u8]> {
+    let len = u32::from_le_bytes(payload.get(*position..*position + 4)?.try_into().ok()?) as usize;
+    let bytes = payload.get(*position + 4..*position + 4 + len)?;
+
+    *position += 4 + len;
+    Some(bytes)
+}
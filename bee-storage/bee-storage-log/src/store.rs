@@ -0,0 +1,349 @@
+// Copyright 2020-2021 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    backend::Error,
+    record::{self, Decoded, Operation, HEADER_LEN},
+};
+
+use log::warn;
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    fs::{self, File, OpenOptions},
+    io::{self, BufReader, BufWriter, Read, Seek, SeekFrom, Write},
+    path::{Path, PathBuf},
+};
+
+const LOG_FILE: &str = "data.log";
+const COMPACTION_FILE: &str = "data.log.compaction";
+
+/// The position of a value in the log.
+#[derive(Clone, Copy)]
+pub(crate) struct Location {
+    offset: u64,
+    len: u32,
+}
+
+/// The locations of the live entries of a keyspace, by packed key.
+pub(crate) type Index = BTreeMap<Vec<u8>, Location>;
+
+/// A log file along with the index of its live entries.
+pub(crate) struct Store {
+    path: PathBuf,
+    file: File,
+    len: u64,
+    /// The length the log would have if it only held its live entries.
+    live: u64,
+    keyspaces: HashMap<u64, Index>,
+}
+
+/// A snapshot of the index of a log, whose live entries are copied to a new log without holding the store.
+pub(crate) struct Compaction {
+    path: PathBuf,
+    reader: File,
+    keyspaces: HashMap<u64, Index>,
+    len: u64,
+}
+
+/// The live entries of a snapshot copied to a new log, that replaces the current one once it is complete.
+pub(crate) struct Compacted {
+    writer: File,
+    keyspaces: HashMap<u64, Index>,
+    len: u64,
+    /// The length of the current log when the snapshot was taken.
+    snapshot_len: u64,
+}
+
+impl Store {
+    /// Open the log of the given directory, creating it if needed, and recover its index.
+    pub(crate) fn open(dir: &Path) -> Result<Self, Error> {
+        fs::create_dir_all(dir)?;
+
+        // A leftover of an interrupted compaction, the log itself being untouched.
+        match fs::remove_file(dir.join(COMPACTION_FILE)) {
+            Err(e) if e.kind() != io::ErrorKind::NotFound => return Err(e.into()),
+            _ => {}
+        }
+
+        let path = dir.join(LOG_FILE);
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(&path)?;
+        let mut store = Self {
+            path,
+            file,
+            len: 0,
+            live: 0,
+            keyspaces: HashMap::new(),
+        };
+
+        store.recover()?;
+
+        Ok(store)
+    }
+
+    /// Replay the log to rebuild the index, truncating the torn record at its end, if any.
+    ///
+    /// Only a record that is incomplete, or whose payload is invalid and is the last one of the log, may have been torn
+    /// by a crash. Any other invalid record, an invalid header included, is a corruption, that fails the recovery rather
+    /// than dropping the records after it.
+    fn recover(&mut self) -> Result<(), Error> {
+        let file_len = self.file.metadata()?.len();
+        let mut reader = BufReader::new(&self.file);
+        let mut offset = 0;
+        let mut header = [0u8; HEADER_LEN];
+
+        while file_len - offset >= HEADER_LEN as u64 {
+            reader.read_exact(&mut header)?;
+
+            // The length of an invalid header can't tell where the record ends, nor whether it is the last one.
+            let (len, checksum) = match record::decode_header(&header) {
+                Some(header) => header,
+                None => return Err(Error::Corrupted(offset, file_len - offset - HEADER_LEN as u64)),
+            };
+            let end = offset + (HEADER_LEN + len) as u64;
+
+            if end > file_len {
+                break;
+            }
+
+            let mut payload = vec![0u8; len];
+            reader.read_exact(&mut payload)?;
+
+            let operations = if crc32fast::hash(&payload) == checksum {
+                record::decode(&payload)
+            } else {
+                None
+            };
+
+            match operations {
+                Some(operations) => apply(&mut self.keyspaces, &mut self.live, offset, &operations),
+                None if end == file_len => break,
+                None => return Err(Error::Corrupted(offset, file_len - end)),
+            }
+
+            offset = end;
+        }
+
+        if offset < file_len {
+            warn!(
+                "Truncating {} bytes of torn records at the end of `{}`.",
+                file_len - offset,
+                self.path.display()
+            );
+            self.file.set_len(offset)?;
+            self.file.sync_all()?;
+        }
+        self.len = offset;
+
+        Ok(())
+    }
+
+    /// Append the operations to the log, as a single record, and apply them to the index.
+    ///
+    /// With `sync`, the record is flushed to the disk before returning.
+    pub(crate) fn append(&mut self, operations: &[Operation], sync: bool) -> io::Result<()> {
+        let record = record::encode(operations);
+        let offset = self.len;
+
+        let written = self
+            .file
+            .seek(SeekFrom::Start(offset))
+            .and_then(|_| self.file.write_all(&record))
+            .and_then(|_| if sync { self.file.sync_data() } else { Ok(()) });
+
+        if let Err(e) = written {
+            // Removes whatever part of the record may have been written.
+            let _ = self.file.set_len(offset);
+            return Err(e);
+        }
+
+        // Encoded records are always valid.
+        if let Some(operations) = record::decode(&record[HEADER_LEN..]) {
+            apply(&mut self.keyspaces, &mut self.live, offset, &operations);
+        }
+        self.len += record.len() as u64;
+
+        Ok(())
+    }
+
+    pub(crate) fn get(&self, keyspace: u64, key: &[u8]) -> Option<Location> {
+        self.keyspaces
+            .get(&keyspace)
+            .and_then(|keyspace| keyspace.get(key))
+            .copied()
+    }
+
+    pub(crate) fn index(&self, keyspace: u64) -> Option<&Index> {
+        self.keyspaces.get(&keyspace)
+    }
+
+    pub(crate) fn read(&mut self, location: Location) -> io::Result<Vec<u8>> {
+        read(&mut self.file, location)
+    }
+
+    pub(crate) fn len(&self) -> u64 {
+        self.len
+    }
+
+    pub(crate) fn sync(&self) -> io::Result<()> {
+        self.file.sync_all()
+    }
+
+    /// Whether at least `ratio` of a log of at least `min_size` bytes is made of dead entries.
+    pub(crate) fn should_compact(&self, ratio: f64, min_size: u64) -> bool {
+        self.len >= min_size && self.len > 0 && self.len.saturating_sub(self.live) as f64 >= ratio * self.len as f64
+    }
+
+    /// Take a snapshot of the index to compact the log, reading it through its own handle.
+    pub(crate) fn compaction(&self) -> io::Result<Compaction> {
+        Ok(Compaction {
+            path: self.path.clone(),
+            reader: File::open(&self.path)?,
+            keyspaces: self.keyspaces.clone(),
+            len: self.len,
+        })
+    }
+
+    /// Replace the log with a compacted one, after appending to it the records appended since its snapshot.
+    ///
+    /// The new log is atomically renamed over the current one once complete, such that an interrupted compaction
+    /// leaves the current log untouched.
+    pub(crate) fn finish_compaction(&mut self, compacted: Compacted) -> io::Result<()> {
+        let Compacted {
+            mut writer,
+            mut keyspaces,
+            len,
+            snapshot_len,
+        } = compacted;
+        let mut tail = vec![0u8; (self.len - snapshot_len) as usize];
+        let mut live = len;
+
+        self.file.seek(SeekFrom::Start(snapshot_len))?;
+        self.file.read_exact(&mut tail)?;
+
+        // The records appended since the snapshot are complete and valid.
+        let mut position = 0;
+        while position < tail.len() {
+            let mut header = [0u8; HEADER_LEN];
+            header.copy_from_slice(&tail[position..position + HEADER_LEN]);
+            let (record_len, _) = record::decode_header(&header).expect("appended records are valid");
+            let payload = &tail[position + HEADER_LEN..position + HEADER_LEN + record_len];
+
+            if let Some(operations) = record::decode(payload) {
+                apply(&mut keyspaces, &mut live, len + position as u64, &operations);
+            }
+            position += HEADER_LEN + record_len;
+        }
+
+        writer.write_all(&tail)?;
+        writer.sync_all()?;
+
+        let compaction_path = self.path.with_file_name(COMPACTION_FILE);
+        fs::rename(&compaction_path, &self.path)?;
+        // Persists the rename, where directories can be synced.
+        if let Some(dir) = self.path.parent() {
+            let _ = File::open(dir).and_then(|dir| dir.sync_all());
+        }
+
+        self.file = OpenOptions::new().read(true).write(true).open(&self.path)?;
+        self.keyspaces = keyspaces;
+        self.len = len + tail.len() as u64;
+        self.live = live;
+
+        Ok(())
+    }
+}
+
+impl Compaction {
+    /// Copy the live entries of the snapshot to a new log, next to the current one.
+    pub(crate) fn copy(mut self) -> io::Result<Compacted> {
+        let compaction_path = self.path.with_file_name(COMPACTION_FILE);
+        let mut writer = BufWriter::new(File::create(&compaction_path)?);
+        let mut keyspaces = HashMap::<u64, Index>::new();
+        let mut len = 0;
+
+        for (keyspace, entries) in self.keyspaces.iter() {
+            let compacted = keyspaces.entry(*keyspace).or_default();
+
+            for (key, location) in entries.iter() {
+                let value = read(&mut self.reader, *location)?;
+                let record = record::encode(&[Operation::Insert {
+                    keyspace: *keyspace,
+                    key: key.clone(),
+                    value,
+                }]);
+
+                writer.write_all(&record)?;
+                // The value ends the record.
+                compacted.insert(
+                    key.clone(),
+                    Location {
+                        offset: len + (record.len() - location.len as usize) as u64,
+                        len: location.len,
+                    },
+                );
+                len += record.len() as u64;
+            }
+        }
+
+        let writer = writer.into_inner().map_err(|e| e.into_error())?;
+        writer.sync_data()?;
+
+        Ok(Compacted {
+            writer,
+            keyspaces,
+            len,
+            snapshot_len: self.len,
+        })
+    }
+}
+
+fn read(file: &mut File, location: Location) -> io::Result<Vec<u8>> {
+    let mut value = vec![0u8; location.len as usize];
+
+    file.seek(SeekFrom::Start(location.offset))?;
+    file.read_exact(&mut value)?;
+
+    Ok(value)
+}
+
+/// Apply the operations of the record at `offset` to the index.
+fn apply(keyspaces: &mut HashMap<u64, Index>, live: &mut u64, offset: u64, operations: &[Decoded<'_>]) {
+    for operation in operations {
+        match operation {
+            Decoded::Insert {
+                keyspace,
+                key,
+                value: (position, len),
+            } => {
+                let location = Location {
+                    offset: offset + (HEADER_LEN + position) as u64,
+                    len: *len,
+                };
+
+                if let Some(previous) = keyspaces.entry(*keyspace).or_default().insert(key.to_vec(), location) {
+                    *live -= record::insert_len(key.len(), previous.len);
+                }
+                *live += record::insert_len(key.len(), *len);
+            }
+            Decoded::Delete { keyspace, key } => {
+                if let Some(previous) = keyspaces.get_mut(keyspace).and_then(|keyspace| keyspace.remove(*key)) {
+                    *live -= record::insert_len(key.len(), previous.len);
+                }
+            }
+            Decoded::Truncate { keyspace } => {
+                if let Some(keyspace) = keyspaces.remove(keyspace) {
+                    *live -= keyspace
+                        .iter()
+                        .map(|(key, location)| record::insert_len(key.len(), location.len))
+                        .sum::<u64>();
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,327 @@
+// Copyright 2020-2021 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+use bee_storage::{
+    access::{
+        AsKeyStream, AsPrefixStream, AsRangeStream, AsReverseStream, AsStream, Batch, BatchBuilder, Delete, Exist,
+        Fetch, Insert, Keyspace, Truncate,
+    },
+    backend::StorageBackend,
+};
+use bee_storage_log::{Error, LogBackend, LogConfig, LogConfigBuilder};
+
+use futures::{executor::block_on, StreamExt};
+use tempfile::TempDir;
+
+use std::{
+    fs::{self, OpenOptions},
+    thread,
+    time::{Duration, Instant},
+};
+
+struct Keyspaces;
+
+impl Keyspace<u32, u64> for Keyspaces {
+    const NAME: &'static str = "u32_to_u64_v1";
+}
+
+impl Keyspace<u32, bool> for Keyspaces {
+    const NAME: &'static str = "u32_to_bool_v1";
+}
+
+type Backend = LogBackend<Keyspaces>;
+
+fn config(dir: &TempDir) -> LogConfig {
+    LogConfigBuilder::new()
+        .path(dir.path())
+        .compaction_interval(Duration::from_secs(3600))
+        .finish()
+}
+
+#[test]
+fn access() {
+    let dir = TempDir::new().unwrap();
+
+    block_on(async {
+        let storage = Backend::start(config(&dir)).await.unwrap();
+
+        Insert::<u32, u64>::insert(&storage, &1, &10).await.unwrap();
+        Insert::<u32, u64>::insert(&storage, &2, &20).await.unwrap();
+        Insert::<u32, bool>::insert(&storage, &1, &true).await.unwrap();
+        Delete::<u32, u64>::delete(&storage, &2).await.unwrap();
+
+        assert_eq!(Fetch::<u32, u64>::fetch(&storage, &1).await.unwrap(), Some(10));
+        assert_eq!(Fetch::<u32, u64>::fetch(&storage, &2).await.unwrap(), None);
+        assert!(Exist::<u32, bool>::exist(&storage, &1).await.unwrap());
+        assert_eq!(
+            Fetch::<u32, u64>::multi_fetch(&storage, &[3, 2, 1]).await.unwrap(),
+            vec![None, None, Some(10)]
+        );
+
+        let entries = AsStream::<u32, u64>::stream(&storage)
+            .await
+            .unwrap()
+            .collect::<Vec<_>>()
+            .await;
+        assert_eq!(entries, vec![(1, 10)]);
+
+        Truncate::<u32, bool>::truncate(&storage).await.unwrap();
+        assert!(!Exist::<u32, bool>::exist(&storage, &1).await.unwrap());
+        assert_eq!(Fetch::<u32, u64>::fetch(&storage, &1).await.unwrap(), Some(10));
+
+        storage.shutdown().await.unwrap();
+    });
+}
+
+#[test]
+fn streams() {
+    let dir = TempDir::new().unwrap();
+
+    block_on(async {
+        let storage = Backend::start(config(&dir)).await.unwrap();
+
+        // Packed little-endian, the keys are ordered as 256, 512, 1, 2.
+        for key in &[1u32, 2, 256, 512] {
+            Insert::<u32, u64>::insert(&storage, key, &(*key as u64 * 10))
+                .await
+                .unwrap();
+        }
+        Insert::<u32, u64>::insert(&storage, &1, &11).await.unwrap();
+
+        let range = AsRangeStream::<u32, u64>::range_stream(&storage, &512, &2)
+            .await
+            .unwrap()
+            .collect::<Vec<_>>()
+            .await;
+        assert_eq!(range, vec![(512, 5120), (1, 11)]);
+
+        let prefix = AsPrefixStream::<u32, u64>::prefix_stream(&storage, &[0])
+            .await
+            .unwrap()
+            .collect::<Vec<_>>()
+            .await;
+        assert_eq!(prefix, vec![(256, 2560), (512, 5120)]);
+
+        let reverse = AsReverseStream::<u32, u64>::reverse_stream(&storage)
+            .await
+            .unwrap()
+            .collect::<Vec<_>>()
+            .await;
+        assert_eq!(reverse, vec![(2, 20), (1, 11), (512, 5120), (256, 2560)]);
+
+        let keys = AsKeyStream::<u32, u64>::key_stream(&storage)
+            .await
+            .unwrap()
+            .collect::<Vec<_>>()
+            .await;
+        assert_eq!(keys, vec![256, 512, 1, 2]);
+
+        storage.shutdown().await.unwrap();
+    });
+}
+
+#[test]
+fn persistence() {
+    let dir = TempDir::new().unwrap();
+
+    block_on(async {
+        let storage = Backend::start(config(&dir)).await.unwrap();
+        let mut batch = Backend::batch_begin();
+
+        Insert::<u32, u64>::insert(&storage, &1, &10).await.unwrap();
+        Insert::<u32, bool>::insert(&storage, &5, &true).await.unwrap();
+        Batch::<u32, u64>::batch_delete(&storage, &mut batch, &1).unwrap();
+        Batch::<u32, u64>::batch_insert(&storage, &mut batch, &2, &20).unwrap();
+        storage.batch_commit(batch, true).await.unwrap();
+        let size = storage.size().await.unwrap();
+        storage.shutdown().await.unwrap();
+
+        let storage = Backend::start(config(&dir)).await.unwrap();
+
+        assert_eq!(storage.size().await.unwrap(), size);
+        assert_eq!(Fetch::<u32, u64>::fetch(&storage, &1).await.unwrap(), None);
+        assert_eq!(Fetch::<u32, u64>::fetch(&storage, &2).await.unwrap(), Some(20));
+        assert_eq!(Fetch::<u32, bool>::fetch(&storage, &5).await.unwrap(), Some(true));
+        storage.shutdown().await.unwrap();
+    });
+}
+
+#[test]
+fn torn_records() {
+    let dir = TempDir::new().unwrap();
+    let log = dir.path().join("data.log");
+
+    block_on(async {
+        let storage = Backend::start(config(&dir)).await.unwrap();
+
+        Insert::<u32, u64>::insert(&storage, &1, &10).await.unwrap();
+        let size = storage.size().await.unwrap().unwrap() as u64;
+        let mut batch = Backend::batch_begin();
+        Batch::<u32, u64>::batch_insert(&storage, &mut batch, &2, &20).unwrap();
+        Batch::<u32, u64>::batch_insert(&storage, &mut batch, &3, &30).unwrap();
+        storage.batch_commit(batch, true).await.unwrap();
+        storage.shutdown().await.unwrap();
+
+        // The batch is torn, as if the node crashed while writing it.
+        let file = OpenOptions::new().write(true).open(&log).unwrap();
+        let len = file.metadata().unwrap().len();
+        file.set_len(len - 3).unwrap();
+
+        let storage = Backend::start(config(&dir)).await.unwrap();
+
+        assert_eq!(storage.size().await.unwrap(), Some(size as usize));
+        assert_eq!(log.metadata().unwrap().len(), size);
+        assert_eq!(Fetch::<u32, u64>::fetch(&storage, &1).await.unwrap(), Some(10));
+        assert_eq!(Fetch::<u32, u64>::fetch(&storage, &2).await.unwrap(), None);
+        assert_eq!(Fetch::<u32, u64>::fetch(&storage, &3).await.unwrap(), None);
+
+        Insert::<u32, u64>::insert(&storage, &4, &40).await.unwrap();
+        storage.shutdown().await.unwrap();
+
+        let storage = Backend::start(config(&dir)).await.unwrap();
+        assert_eq!(Fetch::<u32, u64>::fetch(&storage, &4).await.unwrap(), Some(40));
+        storage.shutdown().await.unwrap();
+    });
+}
+
+#[test]
+fn corrupted_record() {
+    let dir = TempDir::new().unwrap();
+    let log = dir.path().join("data.log");
+
+    block_on(async {
+        let storage = Backend::start(config(&dir)).await.unwrap();
+
+        Insert::<u32, u64>::insert(&storage, &1, &10).await.unwrap();
+        Insert::<u32, u64>::insert(&storage, &2, &20).await.unwrap();
+        storage.shutdown().await.unwrap();
+
+        // The last byte of the value of the first record is flipped.
+        let mut bytes = fs::read(&log).unwrap();
+        let first = bytes.len() / 2;
+        bytes[first - 1] ^= 0xff;
+        fs::write(&log, &bytes).unwrap();
+
+        match Backend::start(config(&dir)).await {
+            Err(Error::Corrupted(0, len)) => assert_eq!(len, first as u64),
+            Err(e) => panic!("unexpected error: {}", e),
+            Ok(_) => panic!("a corrupted log was opened"),
+        }
+        // The valid record after the corrupted one is kept.
+        assert_eq!(fs::read(&log).unwrap(), bytes);
+    });
+}
+
+#[test]
+fn corrupted_record_length() {
+    let dir = TempDir::new().unwrap();
+    let log = dir.path().join("data.log");
+
+    block_on(async {
+        let storage = Backend::start(config(&dir)).await.unwrap();
+
+        Insert::<u32, u64>::insert(&storage, &1, &10).await.unwrap();
+        Insert::<u32, u64>::insert(&storage, &2, &20).await.unwrap();
+        storage.shutdown().await.unwrap();
+
+        // The length of the first record is flipped, such that the record seems to go past the end of the log.
+        let mut bytes = fs::read(&log).unwrap();
+        bytes[1] ^= 0xff;
+        fs::write(&log, &bytes).unwrap();
+
+        match Backend::start(config(&dir)).await {
+            // The header of a record is 12 bytes long.
+            Err(Error::Corrupted(0, len)) => assert_eq!(len, bytes.len() as u64 - 12),
+            Err(e) => panic!("unexpected error: {}", e),
+            Ok(_) => panic!("a corrupted log was opened"),
+        }
+        // The valid record after the corrupted one is kept.
+        assert_eq!(fs::read(&log).unwrap(), bytes);
+    });
+}
+
+#[test]
+fn compaction_with_concurrent_writes() {
+    let dir = TempDir::new().unwrap();
+    let storage = block_on(Backend::start(config(&dir))).unwrap();
+
+    block_on(async {
+        for key in 0..100u32 {
+            Insert::<u32, u64>::insert(&storage, &key, &0).await.unwrap();
+        }
+    });
+
+    thread::scope(|scope| {
+        scope.spawn(|| {
+            for _ in 0..20 {
+                block_on(storage.compact()).unwrap();
+            }
+        });
+
+        block_on(async {
+            for key in 0..100u32 {
+                Insert::<u32, u64>::insert(&storage, &key, &(key as u64)).await.unwrap();
+                Delete::<u32, u64>::delete(&storage, &(key + 1000)).await.unwrap();
+            }
+        });
+    });
+
+    block_on(async {
+        storage.compact().await.unwrap();
+
+        let entries = AsStream::<u32, u64>::stream(&storage)
+            .await
+            .unwrap()
+            .collect::<Vec<_>>()
+            .await;
+        assert_eq!(entries.len(), 100);
+        assert!(entries.iter().all(|(key, value)| *key as u64 == *value));
+        let size = storage.size().await.unwrap();
+        storage.shutdown().await.unwrap();
+
+        let storage = Backend::start(config(&dir)).await.unwrap();
+        assert_eq!(storage.size().await.unwrap(), size);
+        assert_eq!(Fetch::<u32, u64>::fetch(&storage, &99).await.unwrap(), Some(99));
+        storage.shutdown().await.unwrap();
+    });
+}
+
+#[test]
+fn background_compaction() {
+    let dir = TempDir::new().unwrap();
+    let config = LogConfigBuilder::new()
+        .path(dir.path())
+        .compaction_ratio(0.5)
+        .compaction_min_size(0)
+        .compaction_interval(Duration::from_millis(10))
+        .finish();
+
+    block_on(async {
+        let storage = Backend::start(config.clone()).await.unwrap();
+
+        for value in 0..100u64 {
+            Insert::<u32, u64>::insert(&storage, &1, &value).await.unwrap();
+        }
+        Insert::<u32, u64>::insert(&storage, &2, &0).await.unwrap();
+        Delete::<u32, u64>::delete(&storage, &2).await.unwrap();
+
+        let full = storage.size().await.unwrap().unwrap();
+        let start = Instant::now();
+
+        while storage.size().await.unwrap().unwrap() == full {
+            assert!(start.elapsed() < Duration::from_secs(5), "the log was not compacted");
+            thread::sleep(Duration::from_millis(10));
+        }
+
+        let compacted = storage.size().await.unwrap().unwrap();
+        assert!(compacted * 50 < full, "{} -> {}", full, compacted);
+        assert_eq!(Fetch::<u32, u64>::fetch(&storage, &1).await.unwrap(), Some(99));
+        storage.shutdown().await.unwrap();
+
+        let storage = Backend::start(config).await.unwrap();
+        assert_eq!(storage.size().await.unwrap(), Some(compacted));
+        assert_eq!(Fetch::<u32, u64>::fetch(&storage, &1).await.unwrap(), Some(99));
+        assert_eq!(Fetch::<u32, u64>::fetch(&storage, &2).await.unwrap(), None);
+        storage.shutdown().await.unwrap();
+    });
+}
@@ -0,0 +1,295 @@
+// Copyright 2020-2021 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::backend::{Error, MemoryBackend};
+
+use bee_common::packable::Packable;
+use bee_storage::access::{
+    AsKeyStream, AsPrefixStream, AsRangeStream, AsReverseStream, AsStream, Batch, BatchBuilder, Delete, Exist, Fetch,
+    Insert, Truncate,
+};
+
+use async_trait::async_trait;
+use futures::stream::{self, Iter};
+
+use std::{
+    any::{type_name, TypeId},
+    ops::Bound::{Excluded, Included, Unbounded},
+    vec::IntoIter,
+};
+
+fn keyspace<K: 'static, V: 'static>() -> TypeId {
+    TypeId::of::<(K, V)>()
+}
+
+fn unpack<P: Packable>(mut bytes: &[u8]) -> Result<P, Error> {
+    P::unpack(&mut bytes).map_err(|e| Error::Unpack(type_name::<P>(), format!("{:?}", e)))
+}
+
+/// Unpack the selected entries of a keyspace into a stream.
+fn snapshot<'k, K: Packable, V: Packable>(
+    entries: impl Iterator<Item = (&'k Vec<u8>, &'k Vec<u8>)>,
+) -> Result<Iter<IntoIter<(K, V)>>, Error> {
+    let entries = entries
+        .map(|(key, value)| Ok((unpack(key)?, unpack(value)?)))
+        .collect::<Result<Vec<_>, Error>>()?;
+
+    Ok(stream::iter(entries))
+}
+
+enum Operation {
+    Insert(TypeId, Vec<u8>, Vec<u8>),
+    Delete(TypeId, Vec<u8>),
+}
+
+/// A batch of write operations, applied atomically by [`BatchBuilder::batch_commit`].
+#[derive(Default)]
+pub struct MemoryBatch {
+    operations: Vec<Operation>,
+}
+
+#[async_trait]
+impl<K, V> Fetch<K, V> for MemoryBackend
+where
+    K: Packable + Send + Sync + 'static,
+    V: Packable + Send + Sync + 'static,
+{
+    async fn fetch(&self, key: &K) -> Result<Option<V>, Self::Error> {
+        self.read()
+            .keyspace(keyspace::<K, V>())
+            .and_then(|keyspace| keyspace.get(&key.pack_new()))
+            .map(|value| unpack(value))
+            .transpose()
+    }
+
+    /// Fetches all the values under the same lock, such that they are consistent with each other.
+    async fn multi_fetch<'k, I>(&self, keys: I) -> Result<Vec<Option<V>>, Self::Error>
+    where
+        K: 'k,
+        I: IntoIterator<Item = &'k K> + Send,
+        I::IntoIter: Send,
+    {
+        let store = self.read();
+        let keyspace = store.keyspace(keyspace::<K, V>());
+
+        keys.into_iter()
+            .map(|key| {
+                keyspace
+                    .and_then(|keyspace| keyspace.get(&key.pack_new()))
+                    .map(|value| unpack(value))
+                    .transpose()
+            })
+            .collect()
+    }
+}
+
+#[async_trait]
+impl<K, V> Exist<K, V> for MemoryBackend
+where
+    K: Packable + Send + Sync + 'static,
+    V: Packable + Send + Sync + 'static,
+{
+    async fn exist(&self, key: &K) -> Result<bool, Self::Error> {
+        Ok(self
+            .read()
+            .keyspace(keyspace::<K, V>())
+            .map_or(false, |keyspace| keyspace.contains_key(&key.pack_new())))
+    }
+}
+
+#[async_trait]
+impl<K, V> Insert<K, V> for MemoryBackend
+where
+    K: Packable + Send + Sync + 'static,
+    V: Packable + Send + Sync + 'static,
+{
+    async fn insert(&self, key: &K, value: &V) -> Result<(), Self::Error> {
+        let (key, value) = (key.pack_new(), value.pack_new());
+
+        self.write().insert(keyspace::<K, V>(), key, value);
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl<K, V> Delete<K, V> for MemoryBackend
+where
+    K: Packable + Send + Sync + 'static,
+    V: Packable + Send + Sync + 'static,
+{
+    async fn delete(&self, key: &K) -> Result<(), Self::Error> {
+        let key = key.pack_new();
+
+        self.write().delete(keyspace::<K, V>(), &key);
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl<K, V> Truncate<K, V> for MemoryBackend
+where
+    K: Packable + Send + Sync + 'static,
+    V: Packable + Send + Sync + 'static,
+{
+    async fn truncate(&self) -> Result<(), Self::Error> {
+        self.write().truncate(keyspace::<K, V>());
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl<'a, K, V> AsStream<'a, K, V> for MemoryBackend
+where
+    K: Packable + Send + Sync + 'static,
+    V: Packable + Send + Sync + 'static,
+{
+    type Stream = Iter<IntoIter<(K, V)>>;
+
+    /// Streams a snapshot of the keyspace, ordered by packed key, taken when the stream is created.
+    async fn stream(&'a self) -> Result<Self::Stream, Self::Error> {
+        let store = self.read();
+
+        snapshot(
+            store
+                .keyspace(keyspace::<K, V>())
+                .into_iter()
+                .flat_map(|keyspace| keyspace.iter()),
+        )
+    }
+}
+
+#[async_trait]
+impl<'a, K, V> AsRangeStream<'a, K, V> for MemoryBackend
+where
+    K: Packable + Send + Sync + 'static,
+    V: Packable + Send + Sync + 'static,
+{
+    type RangeStream = Iter<IntoIter<(K, V)>>;
+
+    /// Streams a snapshot of the range of the keyspace, taken when the stream is created.
+    async fn range_stream(&'a self, from: &K, to: &K) -> Result<Self::RangeStream, Self::Error> {
+        let (from, to) = (from.pack_new(), to.pack_new());
+        let store = self.read();
+
+        // A range whose start is after its end is empty, but not a valid range of a map.
+        if from >= to {
+            return Ok(stream::iter(Vec::new()));
+        }
+
+        snapshot(
+            store
+                .keyspace(keyspace::<K, V>())
+                .into_iter()
+                .flat_map(|keyspace| keyspace.range::<[u8], _>((Included(from.as_slice()), Excluded(to.as_slice())))),
+        )
+    }
+}
+
+#[async_trait]
+impl<'a, K, V> AsPrefixStream<'a, K, V> for MemoryBackend
+where
+    K: Packable + Send + Sync + 'static,
+    V: Packable + Send + Sync + 'static,
+{
+    type PrefixStream = Iter<IntoIter<(K, V)>>;
+
+    /// Streams a snapshot of the keys of the keyspace with the prefix, taken when the stream is created.
+    async fn prefix_stream(&'a self, prefix: &[u8]) -> Result<Self::PrefixStream, Self::Error> {
+        let store = self.read();
+
+        snapshot(
+            store
+                .keyspace(keyspace::<K, V>())
+                .into_iter()
+                .flat_map(|keyspace| keyspace.range::<[u8], _>((Included(prefix), Unbounded)))
+                .take_while(|(key, _)| key.starts_with(prefix)),
+        )
+    }
+}
+
+#[async_trait]
+impl<'a, K, V> AsReverseStream<'a, K, V> for MemoryBackend
+where
+    K: Packable + Send + Sync + 'static,
+    V: Packable + Send + Sync + 'static,
+{
+    type ReverseStream = Iter<IntoIter<(K, V)>>;
+
+    /// Streams a snapshot of the keyspace, in reverse order, taken when the stream is created.
+    async fn reverse_stream(&'a self) -> Result<Self::ReverseStream, Self::Error> {
+        let store = self.read();
+
+        snapshot(
+            store
+                .keyspace(keyspace::<K, V>())
+                .into_iter()
+                .flat_map(|keyspace| keyspace.iter().rev()),
+        )
+    }
+}
+
+#[async_trait]
+impl<'a, K, V> AsKeyStream<'a, K, V> for MemoryBackend
+where
+    K: Packable + Send + Sync + 'static,
+    V: Packable + Send + Sync + 'static,
+{
+    type KeyStream = Iter<IntoIter<K>>;
+
+    /// Streams a snapshot of the keys of the keyspace, taken when the stream is created.
+    async fn key_stream(&'a self) -> Result<Self::KeyStream, Self::Error> {
+        let keys = self
+            .read()
+            .keyspace(keyspace::<K, V>())
+            .into_iter()
+            .flat_map(|keyspace| keyspace.keys())
+            .map(|key| unpack(key))
+            .collect::<Result<Vec<_>, Error>>()?;
+
+        Ok(stream::iter(keys))
+    }
+}
+
+#[async_trait]
+impl BatchBuilder for MemoryBackend {
+    type Batch = MemoryBatch;
+
+    /// Applies all the operations of the batch at once, in order. Nothing being persisted, `durability` is ignored.
+    async fn batch_commit(&self, batch: Self::Batch, _durability: bool) -> Result<(), Self::Error> {
+        let mut store = self.write();
+
+        for operation in batch.operations {
+            match operation {
+                Operation::Insert(keyspace, key, value) => store.insert(keyspace, key, value),
+                Operation::Delete(keyspace, key) => store.delete(keyspace, &key),
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl<K, V> Batch<K, V> for MemoryBackend
+where
+    K: Packable + Send + Sync + 'static,
+    V: Packable + Send + Sync + 'static,
+{
+    fn batch_insert(&self, batch: &mut Self::Batch, key: &K, value: &V) -> Result<(), Self::Error> {
+        batch
+            .operations
+            .push(Operation::Insert(keyspace::<K, V>(), key.pack_new(), value.pack_new()));
+
+        Ok(())
+    }
+
+    fn batch_delete(&self, batch: &mut Self::Batch, key: &K) -> Result<(), Self::Error> {
+        batch
+            .operations
+            .push(Operation::Delete(keyspace::<K, V>(), key.pack_new()));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,111 @@
+// Copyright 2020-2021 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+use bee_storage::backend::StorageBackend;
+
+use async_trait::async_trait;
+use thiserror::Error;
+
+use std::{
+    any::TypeId,
+    collections::{BTreeMap, HashMap},
+    mem::size_of,
+    sync::{RwLock, RwLockReadGuard, RwLockWriteGuard},
+};
+
+/// The memory used by an entry on top of its key and value bytes, i.e. their vector headers.
+const ENTRY_OVERHEAD: usize = 2 * size_of::<Vec<u8>>();
+
+/// Errors that may occur on memory backend operations.
+#[derive(Error, Debug)]
+pub enum Error {
+    /// A stored key or value could not be unpacked into its type.
+    #[error("Unpacking `{0}` failed: {1}.")]
+    Unpack(&'static str, String),
+}
+
+pub(crate) type Keyspace = BTreeMap<Vec<u8>, Vec<u8>>;
+
+/// The entries of all keyspaces, along with their size.
+#[derive(Default)]
+pub(crate) struct Store {
+    keyspaces: HashMap<TypeId, Keyspace>,
+    size: usize,
+}
+
+impl Store {
+    pub(crate) fn keyspace(&self, keyspace: TypeId) -> Option<&Keyspace> {
+        self.keyspaces.get(&keyspace)
+    }
+
+    pub(crate) fn insert(&mut self, keyspace: TypeId, key: Vec<u8>, value: Vec<u8>) {
+        let key_len = key.len();
+        let added = key_len + value.len() + ENTRY_OVERHEAD;
+
+        // The previous entry of the key, if any, is replaced.
+        if let Some(previous) = self.keyspaces.entry(keyspace).or_default().insert(key, value) {
+            self.size -= key_len + previous.len() + ENTRY_OVERHEAD;
+        }
+        self.size += added;
+    }
+
+    pub(crate) fn delete(&mut self, keyspace: TypeId, key: &[u8]) {
+        if let Some(value) = self.keyspaces.get_mut(&keyspace).and_then(|ks| ks.remove(key)) {
+            self.size -= key.len() + value.len() + ENTRY_OVERHEAD;
+        }
+    }
+
+    pub(crate) fn truncate(&mut self, keyspace: TypeId) {
+        if let Some(keyspace) = self.keyspaces.remove(&keyspace) {
+            self.size -= keyspace
+                .iter()
+                .map(|(key, value)| key.len() + value.len() + ENTRY_OVERHEAD)
+                .sum::<usize>();
+        }
+    }
+}
+
+/// A concurrent in-memory storage backend.
+///
+/// Keys and values are stored packed, in one ordered keyspace per `(K, V)` pair. Any number of readers can access the
+/// backend at once while writes, batches included, are exclusive and atomic.
+#[derive(Default)]
+pub struct MemoryBackend {
+    store: RwLock<Store>,
+}
+
+impl MemoryBackend {
+    /// Create an empty backend.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub(crate) fn read(&self) -> RwLockReadGuard<'_, Store> {
+        self.store.read().unwrap()
+    }
+
+    pub(crate) fn write(&self) -> RwLockWriteGuard<'_, Store> {
+        self.store.write().unwrap()
+    }
+}
+
+#[async_trait]
+impl StorageBackend for MemoryBackend {
+    type ConfigBuilder = ();
+    type Config = ();
+    type Error = Error;
+
+    async fn start(_config: Self::Config) -> Result<Self, Self::Error> {
+        Ok(Self::new())
+    }
+
+    async fn shutdown(self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+
+    /// The memory used by the stored keys and values, including the headers of their buffers but not the structure of
+    /// the keyspaces.
+    async fn size(&self) -> Result<Option<usize>, Self::Error> {
+        Ok(Some(self.read().size))
+    }
+}
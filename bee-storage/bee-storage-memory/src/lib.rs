@@ -0,0 +1,24 @@
+// Copyright 2020-2021 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+//! An in-memory storage backend for the bee framework.
+//!
+//! # Features
+//!
+//! - Implementation of every access trait of `bee-storage`, for any `Packable` key and value types;
+//! - One keyspace per `(K, V)` pair, such that the same key type can be used for different values;
+//! - Concurrent readers, writers and atomic batches;
+//! - `size()` reporting the memory used by the stored entries;
+//!
+//! This backend is mostly meant for tests and ephemeral nodes, nothing being persisted.
+
+#![deny(missing_docs)]
+#![deny(warnings)]
+
+/// Access module which implements the access traits of `bee-storage` for the memory backend.
+mod access;
+/// Backend module which holds the memory backend itself and implements `StorageBackend` for it.
+mod backend;
+
+pub use access::MemoryBatch;
+pub use backend::{Error, MemoryBackend};
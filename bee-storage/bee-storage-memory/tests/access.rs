@@ -0,0 +1,207 @@
+// Copyright 2020-2021 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+use bee_storage::{
+    access::{
+        AsKeyStream, AsPrefixStream, AsRangeStream, AsReverseStream, AsStream, Batch, BatchBuilder, Delete, Exist,
+        Fetch, Insert, Truncate,
+    },
+    backend::StorageBackend,
+};
+use bee_storage_memory::MemoryBackend;
+
+use futures::{executor::block_on, StreamExt};
+
+use std::{sync::Arc, thread};
+
+#[test]
+fn insert_fetch_delete() {
+    block_on(async {
+        let storage = MemoryBackend::start(()).await.unwrap();
+
+        assert_eq!(Fetch::<u32, u64>::fetch(&storage, &1).await.unwrap(), None);
+        assert!(!Exist::<u32, u64>::exist(&storage, &1).await.unwrap());
+
+        Insert::<u32, u64>::insert(&storage, &1, &10).await.unwrap();
+        Insert::<u32, u64>::insert(&storage, &1, &11).await.unwrap();
+
+        assert_eq!(Fetch::<u32, u64>::fetch(&storage, &1).await.unwrap(), Some(11));
+        assert!(Exist::<u32, u64>::exist(&storage, &1).await.unwrap());
+
+        Delete::<u32, u64>::delete(&storage, &1).await.unwrap();
+
+        assert_eq!(Fetch::<u32, u64>::fetch(&storage, &1).await.unwrap(), None);
+        storage.shutdown().await.unwrap();
+    });
+}
+
+#[test]
+fn multi_fetch() {
+    block_on(async {
+        let storage = MemoryBackend::new();
+
+        Insert::<u32, u64>::insert(&storage, &1, &10).await.unwrap();
+        Insert::<u32, u64>::insert(&storage, &2, &20).await.unwrap();
+        Insert::<u32, bool>::insert(&storage, &3, &true).await.unwrap();
+
+        let values = Fetch::<u32, u64>::multi_fetch(&storage, &[2, 3, 1]).await.unwrap();
+        assert_eq!(values, vec![Some(20), None, Some(10)]);
+
+        let values = Fetch::<u32, bool>::multi_fetch(&storage, &[3, 1]).await.unwrap();
+        assert_eq!(values, vec![Some(true), None]);
+    });
+}
+
+#[test]
+fn keyspaces() {
+    block_on(async {
+        let storage = MemoryBackend::new();
+
+        Insert::<u32, u64>::insert(&storage, &1, &10).await.unwrap();
+        Insert::<u32, bool>::insert(&storage, &1, &true).await.unwrap();
+        Insert::<u32, bool>::insert(&storage, &2, &false).await.unwrap();
+
+        Truncate::<u32, bool>::truncate(&storage).await.unwrap();
+
+        assert_eq!(Fetch::<u32, u64>::fetch(&storage, &1).await.unwrap(), Some(10));
+        assert_eq!(Fetch::<u32, bool>::fetch(&storage, &1).await.unwrap(), None);
+        assert_eq!(Fetch::<u32, bool>::fetch(&storage, &2).await.unwrap(), None);
+    });
+}
+
+#[test]
+fn batch() {
+    block_on(async {
+        let storage = MemoryBackend::new();
+        let mut batch = MemoryBackend::batch_begin();
+
+        Insert::<u32, u64>::insert(&storage, &1, &10).await.unwrap();
+        Batch::<u32, u64>::batch_delete(&storage, &mut batch, &1).unwrap();
+        Batch::<u32, u64>::batch_insert(&storage, &mut batch, &2, &20).unwrap();
+        Batch::<u32, bool>::batch_insert(&storage, &mut batch, &3, &true).unwrap();
+
+        // Nothing is applied before the commit.
+        assert_eq!(Fetch::<u32, u64>::fetch(&storage, &1).await.unwrap(), Some(10));
+        assert_eq!(Fetch::<u32, u64>::fetch(&storage, &2).await.unwrap(), None);
+
+        storage.batch_commit(batch, true).await.unwrap();
+
+        assert_eq!(Fetch::<u32, u64>::fetch(&storage, &1).await.unwrap(), None);
+        assert_eq!(Fetch::<u32, u64>::fetch(&storage, &2).await.unwrap(), Some(20));
+        assert_eq!(Fetch::<u32, bool>::fetch(&storage, &3).await.unwrap(), Some(true));
+    });
+}
+
+#[test]
+fn stream_and_size() {
+    block_on(async {
+        let storage = MemoryBackend::new();
+
+        assert_eq!(storage.size().await.unwrap(), Some(0));
+
+        for key in 0..10u32 {
+            Insert::<u32, u64>::insert(&storage, &key, &(key as u64 * 2))
+                .await
+                .unwrap();
+        }
+        let size = storage.size().await.unwrap().unwrap();
+        assert!(size >= 10 * (4 + 8), "{}", size);
+
+        // Overwriting with values of the same size doesn't change the size.
+        Insert::<u32, u64>::insert(&storage, &0, &1).await.unwrap();
+        assert_eq!(storage.size().await.unwrap(), Some(size));
+
+        let mut entries = AsStream::<u32, u64>::stream(&storage)
+            .await
+            .unwrap()
+            .collect::<Vec<_>>()
+            .await;
+        entries.sort_unstable();
+        assert_eq!(entries.len(), 10);
+        assert_eq!(entries[0], (0, 1));
+        assert_eq!(entries[9], (9, 18));
+
+        Truncate::<u32, u64>::truncate(&storage).await.unwrap();
+        assert_eq!(storage.size().await.unwrap(), Some(0));
+    });
+}
+
+#[test]
+fn concurrent_writers() {
+    let storage = Arc::new(MemoryBackend::new());
+
+    let writers = (0..8u32)
+        .map(|writer| {
+            let storage = storage.clone();
+
+            thread::spawn(move || {
+                block_on(async {
+                    for i in 0..100u32 {
+                        Insert::<u32, u32>::insert(&*storage, &(writer * 100 + i), &writer)
+                            .await
+                            .unwrap();
+                    }
+                })
+            })
+        })
+        .collect::<Vec<_>>();
+
+    for writer in writers {
+        writer.join().unwrap();
+    }
+
+    block_on(async {
+        let entries = AsStream::<u32, u32>::stream(&*storage).await.unwrap().count().await;
+
+        assert_eq!(entries, 800);
+        assert_eq!(Fetch::<u32, u32>::fetch(&*storage, &742).await.unwrap(), Some(7));
+    });
+}
+
+#[test]
+fn range_prefix_reverse_keys() {
+    block_on(async {
+        let storage = MemoryBackend::new();
+
+        // Packed little-endian, the keys are ordered as 256, 512, 1, 2.
+        for key in &[1u32, 2, 256, 512] {
+            Insert::<u32, u64>::insert(&storage, key, &(*key as u64 * 10))
+                .await
+                .unwrap();
+        }
+
+        let range = AsRangeStream::<u32, u64>::range_stream(&storage, &512, &2)
+            .await
+            .unwrap()
+            .collect::<Vec<_>>()
+            .await;
+        assert_eq!(range, vec![(512, 5120), (1, 10)]);
+        assert!(AsRangeStream::<u32, u64>::range_stream(&storage, &2, &512)
+            .await
+            .unwrap()
+            .next()
+            .await
+            .is_none());
+
+        let prefix = AsPrefixStream::<u32, u64>::prefix_stream(&storage, &[0])
+            .await
+            .unwrap()
+            .collect::<Vec<_>>()
+            .await;
+        assert_eq!(prefix, vec![(256, 2560), (512, 5120)]);
+
+        let reverse = AsReverseStream::<u32, u64>::reverse_stream(&storage)
+            .await
+            .unwrap()
+            .collect::<Vec<_>>()
+            .await;
+        assert_eq!(reverse, vec![(2, 20), (1, 10), (512, 5120), (256, 2560)]);
+
+        let keys = AsKeyStream::<u32, u64>::key_stream(&storage)
+            .await
+            .unwrap()
+            .collect::<Vec<_>>()
+            .await;
+        assert_eq!(keys, vec![256, 512, 1, 2]);
+    });
+}
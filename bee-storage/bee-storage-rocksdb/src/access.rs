@@ -0,0 +1,327 @@
+// Copyright 2020-2021 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::backend::{Db, Error, RocksDbBackend};
+
+use bee_common::packable::Packable;
+use bee_storage::access::{
+    AsKeyStream, AsPrefixStream, AsRangeStream, AsReverseStream, AsStream, Batch, BatchBuilder, Delete, Exist, Fetch,
+    Insert, Keyspace, Truncate,
+};
+
+use async_trait::async_trait;
+use futures::{
+    stream::Stream,
+    task::{Context, Poll},
+};
+use log::error;
+use rocksdb::{DBIteratorWithThreadMode, IteratorMode, ReadOptions, WriteBatch, WriteOptions};
+
+use std::{any::type_name, marker::PhantomData, pin::Pin};
+
+fn unpack<P: Packable>(mut bytes: &[u8]) -> Result<P, Error> {
+    P::unpack(&mut bytes).map_err(|e| Error::Unpack(type_name::<P>(), format!("{:?}", e)))
+}
+
+/// The smallest key greater than all the keys starting with `prefix`, if any.
+fn successor(prefix: &[u8]) -> Option<Vec<u8>> {
+    let mut successor = prefix.to_vec();
+
+    while let Some(byte) = successor.pop() {
+        if byte < u8::MAX {
+            successor.push(byte + 1);
+            return Some(successor);
+        }
+    }
+
+    None
+}
+
+/// A stream over the entries of a column family, driven by a RocksDB iterator.
+///
+/// The stream ends on the first entry that can't be read or unpacked, after logging the error.
+pub struct RocksDbStream<'a, K, V> {
+    iterator: DBIteratorWithThreadMode<'a, Db>,
+    done: bool,
+    marker: PhantomData<fn() -> (K, V)>,
+}
+
+impl<'a, K, V> RocksDbStream<'a, K, V> {
+    fn new<S: Keyspace<K, V>>(
+        backend: &'a RocksDbBackend<S>,
+        options: ReadOptions,
+        mode: IteratorMode<'_>,
+    ) -> Result<Self, Error> {
+        let column_family = backend.column_family::<K, V>()?;
+
+        Ok(Self {
+            iterator: backend.db.iterator_cf_opt(&column_family, options, mode),
+            done: false,
+            marker: PhantomData,
+        })
+    }
+
+    /// Read the next entry with `f`, ending the stream on the first error, that its items can't carry.
+    fn next_with<T>(&mut self, f: impl FnOnce(&[u8], &[u8]) -> Result<T, Error>) -> Option<T> {
+        if self.done {
+            return None;
+        }
+
+        let entry = self.iterator.next()?;
+
+        match entry.map_err(Error::from).and_then(|(key, value)| f(&key, &value)) {
+            Ok(item) => Some(item),
+            Err(e) => {
+                error!("Streaming column family failed, ending the stream: {}", e);
+                self.done = true;
+                None
+            }
+        }
+    }
+}
+
+impl<'a, K: Packable, V: Packable> Stream for RocksDbStream<'a, K, V> {
+    type Item = (K, V);
+
+    fn poll_next(mut self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        Poll::Ready(self.next_with(|key, value| Ok((unpack(key)?, unpack(value)?))))
+    }
+}
+
+/// A stream over the keys of a column family, driven by a RocksDB iterator.
+///
+/// The stream ends on the first key that can't be read or unpacked, after logging the error.
+pub struct RocksDbKeyStream<'a, K, V> {
+    entries: RocksDbStream<'a, K, V>,
+}
+
+impl<'a, K: Packable, V> Stream for RocksDbKeyStream<'a, K, V> {
+    type Item = K;
+
+    fn poll_next(mut self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        Poll::Ready(self.entries.next_with(|key, _| unpack(key)))
+    }
+}
+
+#[async_trait]
+impl<S, K, V> Fetch<K, V> for RocksDbBackend<S>
+where
+    S: Keyspace<K, V> + 'static,
+    K: Packable + Send + Sync,
+    V: Packable + Send + Sync,
+{
+    async fn fetch(&self, key: &K) -> Result<Option<V>, Self::Error> {
+        let column_family = self.column_family::<K, V>()?;
+
+        match self.db.get_pinned_cf(&column_family, key.pack_new())? {
+            Some(value) => Ok(Some(unpack(&value)?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Fetches all the values with a single RocksDB batched multi-get.
+    async fn multi_fetch<'k, I>(&self, keys: I) -> Result<Vec<Option<V>>, Self::Error>
+    where
+        K: 'k,
+        I: IntoIterator<Item = &'k K> + Send,
+        I::IntoIter: Send,
+    {
+        let column_family = self.column_family::<K, V>()?;
+        let keys = keys.into_iter().map(|key| key.pack_new()).collect::<Vec<_>>();
+
+        self.db
+            .batched_multi_get_cf(&column_family, &keys, false)
+            .into_iter()
+            .map(|value| match value? {
+                Some(value) => Ok(Some(unpack(&value)?)),
+                None => Ok(None),
+            })
+            .collect()
+    }
+}
+
+#[async_trait]
+impl<S, K, V> Exist<K, V> for RocksDbBackend<S>
+where
+    S: Keyspace<K, V> + 'static,
+    K: Packable + Send + Sync,
+    V: Packable + Send + Sync,
+{
+    async fn exist(&self, key: &K) -> Result<bool, Self::Error> {
+        let column_family = self.column_family::<K, V>()?;
+
+        Ok(self.db.get_pinned_cf(&column_family, key.pack_new())?.is_some())
+    }
+}
+
+#[async_trait]
+impl<S, K, V> Insert<K, V> for RocksDbBackend<S>
+where
+    S: Keyspace<K, V> + 'static,
+    K: Packable + Send + Sync,
+    V: Packable + Send + Sync,
+{
+    async fn insert(&self, key: &K, value: &V) -> Result<(), Self::Error> {
+        let column_family = self.column_family::<K, V>()?;
+
+        Ok(self.db.put_cf(&column_family, key.pack_new(), value.pack_new())?)
+    }
+}
+
+#[async_trait]
+impl<S, K, V> Delete<K, V> for RocksDbBackend<S>
+where
+    S: Keyspace<K, V> + 'static,
+    K: Packable + Send + Sync,
+    V: Packable + Send + Sync,
+{
+    async fn delete(&self, key: &K) -> Result<(), Self::Error> {
+        let column_family = self.column_family::<K, V>()?;
+
+        Ok(self.db.delete_cf(&column_family, key.pack_new())?)
+    }
+}
+
+#[async_trait]
+impl<S, K, V> Truncate<K, V> for RocksDbBackend<S>
+where
+    S: Keyspace<K, V> + 'static,
+    K: Packable + Send + Sync,
+    V: Packable + Send + Sync,
+{
+    /// Deletes the whole range of keys of the column family, from the empty key to its last key, in a single write.
+    async fn truncate(&self) -> Result<(), Self::Error> {
+        let column_family = self.column_family::<K, V>()?;
+        let last = match self.db.iterator_cf(&column_family, IteratorMode::End).next() {
+            Some(entry) => entry?.0,
+            None => return Ok(()),
+        };
+        let mut batch = WriteBatch::default();
+
+        // The end of a range deletion is exclusive.
+        batch.delete_range_cf(&column_family, &[][..], &last[..]);
+        batch.delete_cf(&column_family, &last[..]);
+
+        Ok(self.db.write(batch)?)
+    }
+}
+
+#[async_trait]
+impl<'a, S, K, V> AsStream<'a, K, V> for RocksDbBackend<S>
+where
+    S: Keyspace<K, V> + 'static,
+    K: Packable + Send + Sync,
+    V: Packable + Send + Sync,
+{
+    type Stream = RocksDbStream<'a, K, V>;
+
+    /// Streams the column family through a RocksDB iterator, that sees the entries as of the creation of the stream.
+    async fn stream(&'a self) -> Result<Self::Stream, Self::Error> {
+        RocksDbStream::new(self, ReadOptions::default(), IteratorMode::Start)
+    }
+}
+
+#[async_trait]
+impl<'a, S, K, V> AsRangeStream<'a, K, V> for RocksDbBackend<S>
+where
+    S: Keyspace<K, V> + 'static,
+    K: Packable + Send + Sync,
+    V: Packable + Send + Sync,
+{
+    type RangeStream = RocksDbStream<'a, K, V>;
+
+    /// Streams the range through a RocksDB iterator bounded by its start and end.
+    async fn range_stream(&'a self, from: &K, to: &K) -> Result<Self::RangeStream, Self::Error> {
+        let mut options = ReadOptions::default();
+        options.set_iterate_lower_bound(from.pack_new());
+        options.set_iterate_upper_bound(to.pack_new());
+
+        RocksDbStream::new(self, options, IteratorMode::Start)
+    }
+}
+
+#[async_trait]
+impl<'a, S, K, V> AsPrefixStream<'a, K, V> for RocksDbBackend<S>
+where
+    S: Keyspace<K, V> + 'static,
+    K: Packable + Send + Sync,
+    V: Packable + Send + Sync,
+{
+    type PrefixStream = RocksDbStream<'a, K, V>;
+
+    /// Streams the keys with the prefix through a RocksDB iterator bounded by the prefix and its successor.
+    async fn prefix_stream(&'a self, prefix: &[u8]) -> Result<Self::PrefixStream, Self::Error> {
+        let mut options = ReadOptions::default();
+        options.set_iterate_lower_bound(prefix);
+        if let Some(successor) = successor(prefix) {
+            options.set_iterate_upper_bound(successor);
+        }
+
+        RocksDbStream::new(self, options, IteratorMode::Start)
+    }
+}
+
+#[async_trait]
+impl<'a, S, K, V> AsReverseStream<'a, K, V> for RocksDbBackend<S>
+where
+    S: Keyspace<K, V> + 'static,
+    K: Packable + Send + Sync,
+    V: Packable + Send + Sync,
+{
+    type ReverseStream = RocksDbStream<'a, K, V>;
+
+    /// Streams the column family through a RocksDB iterator going backward from its last key.
+    async fn reverse_stream(&'a self) -> Result<Self::ReverseStream, Self::Error> {
+        RocksDbStream::new(self, ReadOptions::default(), IteratorMode::End)
+    }
+}
+
+#[async_trait]
+impl<'a, S, K, V> AsKeyStream<'a, K, V> for RocksDbBackend<S>
+where
+    S: Keyspace<K, V> + 'static,
+    K: Packable + Send + Sync,
+    V: Packable + Send + Sync,
+{
+    type KeyStream = RocksDbKeyStream<'a, K, V>;
+
+    /// Streams the keys of the column family through a RocksDB iterator, without unpacking the values.
+    async fn key_stream(&'a self) -> Result<Self::KeyStream, Self::Error> {
+        Ok(RocksDbKeyStream {
+            entries: RocksDbStream::new(self, ReadOptions::default(), IteratorMode::Start)?,
+        })
+    }
+}
+
+#[async_trait]
+impl<S: 'static> BatchBuilder for RocksDbBackend<S> {
+    type Batch = WriteBatch;
+
+    /// Writes the batch atomically, through the write-ahead log if `durability` is set, and bypassing it otherwise,
+    /// in which case the batch may be lost on a crash until its column families are flushed.
+    async fn batch_commit(&self, batch: Self::Batch, durability: bool) -> Result<(), Self::Error> {
+        let mut options = WriteOptions::default();
+        options.disable_wal(!durability);
+
+        Ok(self.db.write_opt(batch, &options)?)
+    }
+}
+
+impl<S, K, V> Batch<K, V> for RocksDbBackend<S>
+where
+    S: Keyspace<K, V> + 'static,
+    K: Packable + Send + Sync,
+    V: Packable + Send + Sync,
+{
+    fn batch_insert(&self, batch: &mut Self::Batch, key: &K, value: &V) -> Result<(), Self::Error> {
+        batch.put_cf(&self.column_family::<K, V>()?, key.pack_new(), value.pack_new());
+
+        Ok(())
+    }
+
+    fn batch_delete(&self, batch: &mut Self::Batch, key: &K) -> Result<(), Self::Error> {
+        batch.delete_cf(&self.column_family::<K, V>()?, key.pack_new());
+
+        Ok(())
+    }
+}
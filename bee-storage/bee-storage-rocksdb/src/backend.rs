@@ -0,0 +1,138 @@
+// Copyright 2020-2021 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::config::{RocksDbConfig, RocksDbConfigBuilder};
+
+use bee_storage::{access::Keyspace, backend::StorageBackend};
+
+use async_trait::async_trait;
+use rocksdb::{
+    BlockBasedOptions, BoundColumnFamily, Cache, ColumnFamilyDescriptor, DBWithThreadMode, MultiThreaded, Options,
+    DEFAULT_COLUMN_FAMILY_NAME,
+};
+use thiserror::Error;
+
+use std::{
+    marker::PhantomData,
+    sync::{Arc, Mutex},
+};
+
+pub(crate) type Db = DBWithThreadMode<MultiThreaded>;
+
+/// Errors that may occur on RocksDB backend operations.
+#[derive(Error, Debug)]
+pub enum Error {
+    /// RocksDB itself failed.
+    #[error("RocksDB error: {0}.")]
+    RocksDb(#[from] rocksdb::Error),
+    /// A stored key or value could not be unpacked into its type.
+    #[error("Unpacking `{0}` failed: {1}.")]
+    Unpack(&'static str, String),
+}
+
+/// A persistent storage backend on top of RocksDB, storing each `(K, V)` pair in its own column family.
+///
+/// Column families are named by the `Keyspace<K, V>` implementation of `S`, created on the first access to their pair
+/// and reopened along with the database.
+pub struct RocksDbBackend<S> {
+    pub(crate) db: Db,
+    options: Options,
+    /// The names of the column families of the database, also serializing their creation.
+    column_families: Mutex<Vec<String>>,
+    marker: PhantomData<fn() -> S>,
+}
+
+impl<S> RocksDbBackend<S> {
+    /// Returns the column family of the `(K, V)` pair, creating it if needed.
+    pub(crate) fn column_family<K, V>(&self) -> Result<Arc<BoundColumnFamily<'_>>, Error>
+    where
+        S: Keyspace<K, V>,
+    {
+        let name = S::NAME;
+
+        if let Some(column_family) = self.db.cf_handle(name) {
+            return Ok(column_family);
+        }
+
+        let mut column_families = self.column_families.lock().unwrap();
+
+        if !column_families.iter().any(|column_family| column_family == name) {
+            self.db.create_cf(name, &self.options)?;
+            column_families.push(name.to_owned());
+        }
+
+        Ok(self.db.cf_handle(name).expect("a created column family has a handle"))
+    }
+}
+
+fn options(config: &RocksDbConfig) -> Options {
+    let mut table_options = BlockBasedOptions::default();
+    table_options.set_block_cache(&Cache::new_lru_cache(config.cache_size));
+
+    let mut options = Options::default();
+    options.create_if_missing(true);
+    options.create_missing_column_families(true);
+    options.set_compression_type(config.compression.into());
+    options.set_max_background_jobs(config.background_jobs as i32);
+    options.set_block_based_table_factory(&table_options);
+
+    options
+}
+
+#[async_trait]
+impl<S: 'static> StorageBackend for RocksDbBackend<S> {
+    type ConfigBuilder = RocksDbConfigBuilder;
+    type Config = RocksDbConfig;
+    type Error = Error;
+
+    async fn start(config: Self::Config) -> Result<Self, Self::Error> {
+        let options = options(&config);
+        // A database that does not exist yet, without a `CURRENT` file, only has the default column family.
+        let column_families = if config.path.join("CURRENT").exists() {
+            Db::list_cf(&options, &config.path)?
+        } else {
+            vec![DEFAULT_COLUMN_FAMILY_NAME.to_owned()]
+        };
+        let db = Db::open_cf_descriptors(
+            &options,
+            &config.path,
+            column_families
+                .iter()
+                .map(|name| ColumnFamilyDescriptor::new(name, options.clone())),
+        )?;
+
+        Ok(Self {
+            db,
+            options,
+            column_families: Mutex::new(column_families),
+            marker: PhantomData,
+        })
+    }
+
+    /// Flushes the memtables of every column family, such that the writes made without durability are persisted.
+    async fn shutdown(self) -> Result<(), Self::Error> {
+        for name in self.column_families.lock().unwrap().iter() {
+            if let Some(column_family) = self.db.cf_handle(name) {
+                self.db.flush_cf(&column_family)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// The size of the SST files of every column family, the writes still in the memtables excluded.
+    async fn size(&self) -> Result<Option<usize>, Self::Error> {
+        let mut size = 0;
+
+        for name in self.column_families.lock().unwrap().iter() {
+            if let Some(column_family) = self.db.cf_handle(name) {
+                size += self
+                    .db
+                    .property_int_value_cf(&column_family, "rocksdb.total-sst-files-size")?
+                    .unwrap_or(0);
+            }
+        }
+
+        Ok(Some(size as usize))
+    }
+}
@@ -0,0 +1,123 @@
+// Copyright 2020-2021 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+use rocksdb::DBCompressionType;
+use serde::Deserialize;
+
+use std::path::PathBuf;
+
+const DEFAULT_PATH: &str = "./storage";
+const DEFAULT_CACHE_SIZE: usize = 64 * 1024 * 1024;
+const DEFAULT_COMPRESSION: Compression = Compression::Snappy;
+const DEFAULT_BACKGROUND_JOBS: u32 = 2;
+
+/// Compression algorithm of the blocks written to the disk.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum Compression {
+    /// No compression.
+    None,
+    /// Snappy compression.
+    Snappy,
+    /// Zlib compression.
+    Zlib,
+    /// Bzip2 compression.
+    Bz2,
+    /// LZ4 compression.
+    Lz4,
+    /// LZ4 high compression.
+    Lz4hc,
+    /// Zstandard compression.
+    Zstd,
+}
+
+impl From<Compression> for DBCompressionType {
+    fn from(compression: Compression) -> Self {
+        match compression {
+            Compression::None => DBCompressionType::None,
+            Compression::Snappy => DBCompressionType::Snappy,
+            Compression::Zlib => DBCompressionType::Zlib,
+            Compression::Bz2 => DBCompressionType::Bz2,
+            Compression::Lz4 => DBCompressionType::Lz4,
+            Compression::Lz4hc => DBCompressionType::Lz4hc,
+            Compression::Zstd => DBCompressionType::Zstd,
+        }
+    }
+}
+
+/// Builder for a [`RocksDbConfig`].
+#[derive(Default, Deserialize)]
+pub struct RocksDbConfigBuilder {
+    path: Option<PathBuf>,
+    cache_size: Option<usize>,
+    compression: Option<Compression>,
+    background_jobs: Option<u32>,
+}
+
+impl RocksDbConfigBuilder {
+    /// Creates a new builder for a RocksDB backend configuration.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the directory that holds the database.
+    pub fn path<P: Into<PathBuf>>(mut self, path: P) -> Self {
+        self.path.replace(path.into());
+        self
+    }
+
+    /// Sets the size, in bytes, of the block cache shared by all the column families.
+    pub fn cache_size(mut self, size: usize) -> Self {
+        self.cache_size.replace(size);
+        self
+    }
+
+    /// Sets the compression algorithm of the blocks written to the disk.
+    pub fn compression(mut self, compression: Compression) -> Self {
+        self.compression.replace(compression);
+        self
+    }
+
+    /// Sets the maximum number of concurrent background flushes and compactions.
+    pub fn background_jobs(mut self, jobs: u32) -> Self {
+        self.background_jobs.replace(jobs);
+        self
+    }
+
+    /// Builds a RocksDB backend configuration.
+    pub fn finish(self) -> RocksDbConfig {
+        RocksDbConfig {
+            path: self.path.unwrap_or_else(|| PathBuf::from(DEFAULT_PATH)),
+            cache_size: self.cache_size.unwrap_or(DEFAULT_CACHE_SIZE),
+            compression: self.compression.unwrap_or(DEFAULT_COMPRESSION),
+            background_jobs: self.background_jobs.unwrap_or(DEFAULT_BACKGROUND_JOBS),
+        }
+    }
+}
+
+impl From<RocksDbConfigBuilder> for RocksDbConfig {
+    fn from(builder: RocksDbConfigBuilder) -> Self {
+        builder.finish()
+    }
+}
+
+/// Configuration of a [`RocksDbBackend`](crate::RocksDbBackend).
+#[derive(Clone, Debug)]
+pub struct RocksDbConfig {
+    pub(crate) path: PathBuf,
+    pub(crate) cache_size: usize,
+    pub(crate) compression: Compression,
+    pub(crate) background_jobs: u32,
+}
+
+impl RocksDbConfig {
+    /// Creates a new builder for a RocksDB backend configuration.
+    pub fn build() -> RocksDbConfigBuilder {
+        RocksDbConfigBuilder::new()
+    }
+
+    /// Returns the directory that holds the database.
+    pub fn path(&self) -> &PathBuf {
+        &self.path
+    }
+}
@@ -0,0 +1,37 @@
+// Copyright 2020-2021 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+//! A RocksDB storage backend for the bee framework.
+//!
+//! # Features
+//!
+//! - Implementation of every access trait of `bee-storage`, for any `Packable` key and value types;
+//! - One column family per `(K, V)` pair, named by its `Keyspace` implementation and created on its first access;
+//! - Atomic batches as RocksDB write batches, going through the write-ahead log when durability is requested;
+//! - Streams, range, prefix, reverse and key streams as RocksDB iterators;
+//! - Configurable block cache size, compression and number of background jobs;
+//!
+//! Renaming the keyspace of a pair makes its previous entries unreachable.
+//!
+//! The backend is only built with the `rocksdb` feature, as building RocksDB requires `libclang` to generate its
+//! bindings.
+
+#![deny(missing_docs)]
+#![deny(warnings)]
+
+/// Access module which implements the access traits of `bee-storage` for the RocksDB backend.
+#[cfg(feature = "rocksdb")]
+mod access;
+/// Backend module which holds the RocksDB backend itself and implements `StorageBackend` for it.
+#[cfg(feature = "rocksdb")]
+mod backend;
+/// Config module which holds the configuration of the RocksDB backend and its builder.
+#[cfg(feature = "rocksdb")]
+mod config;
+
+#[cfg(feature = "rocksdb")]
+pub use access::{RocksDbKeyStream, RocksDbStream};
+#[cfg(feature = "rocksdb")]
+pub use backend::{Error, RocksDbBackend};
+#[cfg(feature = "rocksdb")]
+pub use config::{Compression, RocksDbConfig, RocksDbConfigBuilder};
@@ -0,0 +1,216 @@
+// Copyright 2020-2021 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+#![cfg(feature = "rocksdb")]
+
+use bee_storage::{
+    access::{
+        AsKeyStream, AsPrefixStream, AsRangeStream, AsReverseStream, AsStream, Batch, BatchBuilder, Delete, Exist,
+        Fetch, Insert, Keyspace, Truncate,
+    },
+    backend::StorageBackend,
+};
+use bee_storage_rocksdb::{Compression, RocksDbBackend, RocksDbConfig, RocksDbConfigBuilder};
+
+use futures::{executor::block_on, StreamExt};
+use tempfile::TempDir;
+
+struct Keyspaces;
+
+impl Keyspace<u32, u64> for Keyspaces {
+    const NAME: &'static str = "u32_to_u64_v1";
+}
+
+impl Keyspace<u32, bool> for Keyspaces {
+    const NAME: &'static str = "u32_to_bool_v1";
+}
+
+// Shares the column family of the `(u32, u64)` pair, to store values that can't be unpacked as `u64`.
+impl Keyspace<u32, u32> for Keyspaces {
+    const NAME: &'static str = "u32_to_u64_v1";
+}
+
+type Backend = RocksDbBackend<Keyspaces>;
+
+fn config(dir: &TempDir) -> RocksDbConfig {
+    RocksDbConfigBuilder::new()
+        .path(dir.path())
+        .cache_size(1024 * 1024)
+        .compression(Compression::None)
+        .background_jobs(1)
+        .finish()
+}
+
+#[test]
+fn access() {
+    let dir = TempDir::new().unwrap();
+
+    block_on(async {
+        let storage = Backend::start(config(&dir)).await.unwrap();
+
+        Insert::<u32, u64>::insert(&storage, &1, &10).await.unwrap();
+        Insert::<u32, u64>::insert(&storage, &2, &20).await.unwrap();
+        Insert::<u32, u64>::insert(&storage, &3, &30).await.unwrap();
+        Insert::<u32, bool>::insert(&storage, &1, &true).await.unwrap();
+        Delete::<u32, u64>::delete(&storage, &2).await.unwrap();
+
+        assert_eq!(Fetch::<u32, u64>::fetch(&storage, &1).await.unwrap(), Some(10));
+        assert_eq!(Fetch::<u32, u64>::fetch(&storage, &2).await.unwrap(), None);
+        assert!(Exist::<u32, bool>::exist(&storage, &1).await.unwrap());
+        assert_eq!(
+            Fetch::<u32, u64>::multi_fetch(&storage, &[3, 2, 1]).await.unwrap(),
+            vec![Some(30), None, Some(10)]
+        );
+
+        let entries = AsStream::<u32, u64>::stream(&storage)
+            .await
+            .unwrap()
+            .collect::<Vec<_>>()
+            .await;
+        assert_eq!(entries, vec![(1, 10), (3, 30)]);
+
+        Truncate::<u32, u64>::truncate(&storage).await.unwrap();
+        assert!(!Exist::<u32, u64>::exist(&storage, &1).await.unwrap());
+        assert!(!Exist::<u32, u64>::exist(&storage, &3).await.unwrap());
+        assert!(Exist::<u32, bool>::exist(&storage, &1).await.unwrap());
+
+        storage.shutdown().await.unwrap();
+    });
+}
+
+#[test]
+fn streams() {
+    let dir = TempDir::new().unwrap();
+
+    block_on(async {
+        let storage = Backend::start(config(&dir)).await.unwrap();
+
+        // Packed little-endian, the keys are ordered as 256, 512, 1, 2.
+        for key in &[1u32, 2, 256, 512] {
+            Insert::<u32, u64>::insert(&storage, key, &(*key as u64 * 10))
+                .await
+                .unwrap();
+        }
+        Insert::<u32, u64>::insert(&storage, &u32::MAX, &0).await.unwrap();
+
+        let range = AsRangeStream::<u32, u64>::range_stream(&storage, &512, &2)
+            .await
+            .unwrap()
+            .collect::<Vec<_>>()
+            .await;
+        assert_eq!(range, vec![(512, 5120), (1, 10)]);
+
+        let prefix = AsPrefixStream::<u32, u64>::prefix_stream(&storage, &[0])
+            .await
+            .unwrap()
+            .collect::<Vec<_>>()
+            .await;
+        assert_eq!(prefix, vec![(256, 2560), (512, 5120)]);
+
+        // A prefix of maximum bytes has no upper bound.
+        let prefix = AsPrefixStream::<u32, u64>::prefix_stream(&storage, &[u8::MAX])
+            .await
+            .unwrap()
+            .collect::<Vec<_>>()
+            .await;
+        assert_eq!(prefix, vec![(u32::MAX, 0)]);
+
+        let reverse = AsReverseStream::<u32, u64>::reverse_stream(&storage)
+            .await
+            .unwrap()
+            .collect::<Vec<_>>()
+            .await;
+        assert_eq!(reverse, vec![(u32::MAX, 0), (2, 20), (1, 10), (512, 5120), (256, 2560)]);
+
+        let keys = AsKeyStream::<u32, u64>::key_stream(&storage)
+            .await
+            .unwrap()
+            .collect::<Vec<_>>()
+            .await;
+        assert_eq!(keys, vec![256, 512, 1, 2, u32::MAX]);
+
+        storage.shutdown().await.unwrap();
+    });
+}
+
+#[test]
+fn persistence() {
+    let dir = TempDir::new().unwrap();
+
+    block_on(async {
+        let storage = Backend::start(config(&dir)).await.unwrap();
+        let mut batch = Backend::batch_begin();
+
+        Insert::<u32, u64>::insert(&storage, &1, &10).await.unwrap();
+        Insert::<u32, bool>::insert(&storage, &5, &true).await.unwrap();
+        Batch::<u32, u64>::batch_delete(&storage, &mut batch, &1).unwrap();
+        Batch::<u32, u64>::batch_insert(&storage, &mut batch, &2, &20).unwrap();
+        storage.batch_commit(batch, true).await.unwrap();
+        storage.shutdown().await.unwrap();
+
+        let storage = Backend::start(config(&dir)).await.unwrap();
+
+        assert_eq!(Fetch::<u32, u64>::fetch(&storage, &1).await.unwrap(), None);
+        assert_eq!(Fetch::<u32, u64>::fetch(&storage, &2).await.unwrap(), Some(20));
+        assert_eq!(Fetch::<u32, bool>::fetch(&storage, &5).await.unwrap(), Some(true));
+        storage.shutdown().await.unwrap();
+    });
+}
+
+#[test]
+fn batch_without_durability() {
+    let dir = TempDir::new().unwrap();
+
+    block_on(async {
+        let storage = Backend::start(config(&dir)).await.unwrap();
+        let mut batch = Backend::batch_begin();
+
+        Batch::<u32, u64>::batch_insert(&storage, &mut batch, &1, &10).unwrap();
+        Batch::<u32, u64>::batch_insert(&storage, &mut batch, &2, &20).unwrap();
+        storage.batch_commit(batch, false).await.unwrap();
+
+        assert_eq!(Fetch::<u32, u64>::fetch(&storage, &2).await.unwrap(), Some(20));
+        // Bypassing the write-ahead log, the batch is persisted by the flush of the shutdown.
+        storage.shutdown().await.unwrap();
+
+        let storage = Backend::start(config(&dir)).await.unwrap();
+
+        assert_eq!(Fetch::<u32, u64>::fetch(&storage, &1).await.unwrap(), Some(10));
+        assert_eq!(Fetch::<u32, u64>::fetch(&storage, &2).await.unwrap(), Some(20));
+        assert!(storage.size().await.unwrap().unwrap() > 0);
+        storage.shutdown().await.unwrap();
+    });
+}
+
+#[test]
+fn unreadable_entry_ends_stream() {
+    let dir = TempDir::new().unwrap();
+
+    block_on(async {
+        let storage = Backend::start(config(&dir)).await.unwrap();
+
+        Insert::<u32, u64>::insert(&storage, &1, &10).await.unwrap();
+        Insert::<u32, u32>::insert(&storage, &2, &20).await.unwrap();
+        Insert::<u32, u64>::insert(&storage, &3, &30).await.unwrap();
+
+        let entries = AsStream::<u32, u64>::stream(&storage)
+            .await
+            .unwrap()
+            .collect::<Vec<_>>()
+            .await;
+        assert_eq!(entries, vec![(1, 10)]);
+
+        storage.shutdown().await.unwrap();
+    });
+}
+
+#[test]
+fn corrupted_database() {
+    let dir = TempDir::new().unwrap();
+
+    std::fs::write(dir.path().join("CURRENT"), b"garbage").unwrap();
+
+    block_on(async {
+        assert!(Backend::start(config(&dir)).await.is_err());
+    });
+}
@@ -0,0 +1,124 @@
+// Copyright 2020-2021 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::access::{AsKeyStream, AsPrefixStream, AsRangeStream, AsReverseStream, AsStream};
+
+use bee_common::packable::Packable;
+
+use futures::{
+    future,
+    stream::{self, Iter, Stream, StreamExt},
+};
+
+use std::{iter::Rev, vec::IntoIter};
+
+/// `StreamFallback` marker trait implements `AsRangeStream`, `AsPrefixStream`, `AsReverseStream` and `AsKeyStream` on
+/// top of `AsStream` for a `StorageBackend` that doesn't implement them natively.
+///
+/// The fallback streams collect the whole <K, V> collection, including its values, and sort it by packed key, before
+/// streaming the matching entries.
+pub trait StreamFallback {}
+
+enum KeyFilter {
+    All,
+    Range(Vec<u8>, Vec<u8>),
+    Prefix(Vec<u8>),
+}
+
+impl KeyFilter {
+    fn matches(&self, key: &[u8]) -> bool {
+        match self {
+            KeyFilter::All => true,
+            KeyFilter::Range(from, to) => from.as_slice() <= key && key < to.as_slice(),
+            KeyFilter::Prefix(prefix) => key.starts_with(prefix),
+        }
+    }
+}
+
+/// Collect the entries of an `AsStream` stream whose packed key matches `filter`, sorted by packed key.
+async fn sorted<S, K, V>(stream: S, filter: KeyFilter) -> Vec<(K, V)>
+where
+    S: Stream<Item = (K, V)>,
+    K: Packable,
+{
+    let mut entries = stream
+        .map(|(key, value)| (key.pack_new(), (key, value)))
+        .filter(|(packed, _)| future::ready(filter.matches(packed)))
+        .collect::<Vec<_>>()
+        .await;
+
+    entries.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+    entries.into_iter().map(|(_, entry)| entry).collect()
+}
+
+#[async_trait::async_trait]
+impl<'a, B, K, V> AsRangeStream<'a, K, V> for B
+where
+    B: AsStream<'a, K, V> + StreamFallback,
+    K: Packable + Send + Sync,
+    V: Send + Sync,
+{
+    type RangeStream = Iter<IntoIter<(K, V)>>;
+
+    async fn range_stream(&'a self, from: &K, to: &K) -> Result<Self::RangeStream, Self::Error> {
+        let filter = KeyFilter::Range(from.pack_new(), to.pack_new());
+
+        Ok(stream::iter(
+            sorted(AsStream::<K, V>::stream(self).await?, filter).await,
+        ))
+    }
+}
+
+#[async_trait::async_trait]
+impl<'a, B, K, V> AsPrefixStream<'a, K, V> for B
+where
+    B: AsStream<'a, K, V> + StreamFallback,
+    K: Packable + Send + Sync,
+    V: Send + Sync,
+{
+    type PrefixStream = Iter<IntoIter<(K, V)>>;
+
+    async fn prefix_stream(&'a self, prefix: &[u8]) -> Result<Self::PrefixStream, Self::Error> {
+        let filter = KeyFilter::Prefix(prefix.to_vec());
+
+        Ok(stream::iter(
+            sorted(AsStream::<K, V>::stream(self).await?, filter).await,
+        ))
+    }
+}
+
+#[async_trait::async_trait]
+impl<'a, B, K, V> AsReverseStream<'a, K, V> for B
+where
+    B: AsStream<'a, K, V> + StreamFallback,
+    K: Packable + Send + Sync,
+    V: Send + Sync,
+{
+    type ReverseStream = Iter<Rev<IntoIter<(K, V)>>>;
+
+    async fn reverse_stream(&'a self) -> Result<Self::ReverseStream, Self::Error> {
+        let entries = sorted(AsStream::<K, V>::stream(self).await?, KeyFilter::All).await;
+
+        Ok(stream::iter(entries.into_iter().rev()))
+    }
+}
+
+#[async_trait::async_trait]
+impl<'a, B, K, V> AsKeyStream<'a, K, V> for B
+where
+    B: AsStream<'a, K, V> + StreamFallback,
+    K: Packable + Send + Sync,
+    V: Send + Sync,
+{
+    type KeyStream = Iter<IntoIter<K>>;
+
+    /// Reads the values along with the keys, through `AsStream`.
+    async fn key_stream(&'a self) -> Result<Self::KeyStream, Self::Error> {
+        let entries = sorted(AsStream::<K, V>::stream(self).await?, KeyFilter::All).await;
+
+        Ok(stream::iter(
+            entries.into_iter().map(|(key, _)| key).collect::<Vec<_>>(),
+        ))
+    }
+}
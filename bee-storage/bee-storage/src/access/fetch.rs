@@ -3,10 +3,30 @@
 
 use crate::backend::StorageBackend;
 
-/// `Fetch<K, V>` trait extends the `StorageBackend` with `fetch` operation for the (key: K, value: V pair);
-/// therefore, it should be explicitly implemented for the corresponding `StorageBackend`.
+/// `Fetch<K, V>` trait extends the `StorageBackend` with `fetch` and `multi_fetch` operations for the (key: K, value: V
+/// pair); therefore, it should be explicitly implemented for the corresponding `StorageBackend`.
 #[async_trait::async_trait]
 pub trait Fetch<K, V>: StorageBackend {
     /// Fetches the value associated with the key from the storage.
     async fn fetch(&self, key: &K) -> Result<Option<V>, Self::Error>;
+
+    /// Fetches the values associated with the keys from the storage, in the order of the keys.
+    ///
+    /// The default implementation fetches the keys one after the other, and should be overridden by backends that can
+    /// batch the lookups.
+    async fn multi_fetch<'k, I>(&self, keys: I) -> Result<Vec<Option<V>>, Self::Error>
+    where
+        K: 'k + Sync,
+        V: Send,
+        I: IntoIterator<Item = &'k K> + Send,
+        I::IntoIter: Send,
+    {
+        let mut values = Vec::new();
+
+        for key in keys {
+            values.push(self.fetch(key).await?);
+        }
+
+        Ok(values)
+    }
 }
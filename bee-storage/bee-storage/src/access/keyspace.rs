@@ -0,0 +1,12 @@
+// Copyright 2020-2021 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+/// `Keyspace<K, V>` trait names the keyspace in which persistent backends store the (key: K, value: V) pair;
+/// therefore, it should be explicitly implemented, for every stored pair, by the type listing the keyspaces of a node.
+///
+/// The name identifies the stored entries of the pair across builds and versions of the node: it must be unique among
+/// the pairs of the node, and be changed, e.g. by bumping a version suffix, when the encoding of the pair changes.
+pub trait Keyspace<K, V> {
+    /// The name of the keyspace, e.g. `"message_id_to_message_v1"`.
+    const NAME: &'static str;
+}
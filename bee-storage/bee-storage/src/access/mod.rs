@@ -7,10 +7,14 @@ mod batch;
 mod delete;
 /// Holds the contract for exist access operation.
 mod exist;
+/// Holds the fallbacks of the range, prefix, reverse and key stream access operations on top of the stream one.
+mod fallback;
 /// Holds the contract for fetch access operation.
 mod fetch;
 /// Holds the contract for insert access operation.
 mod insert;
+/// Holds the contract naming the keyspaces of persistent backends.
+mod keyspace;
 /// Holds the contract for stream access operations.
 mod stream;
 /// Holds the contract for truncate access operations.
@@ -19,7 +23,9 @@ mod truncate;
 pub use batch::{Batch, BatchBuilder};
 pub use delete::Delete;
 pub use exist::Exist;
+pub use fallback::StreamFallback;
 pub use fetch::Fetch;
 pub use insert::Insert;
-pub use stream::AsStream;
+pub use keyspace::Keyspace;
+pub use stream::{AsKeyStream, AsPrefixStream, AsRangeStream, AsReverseStream, AsStream};
 pub use truncate::Truncate;
@@ -15,3 +15,50 @@ pub trait AsStream<'a, K, V>: StorageBackend {
     /// Returns a `Stream` object for the provided <K, V> collection.
     async fn stream(&'a self) -> Result<Self::Stream, Self::Error>;
 }
+
+/// `AsRangeStream<'a, K, V>` trait extends the `StorageBackend` with `range_stream` operation for the (key: K, value: V)
+/// pair; therefore, it should be explicitly implemented for the corresponding `StorageBackend`.
+#[async_trait::async_trait]
+pub trait AsRangeStream<'a, K, V>: StorageBackend {
+    /// Type to iterate through a range of the <K, V> collection.
+    type RangeStream: Stream<Item = (K, V)> + Send + Sync + Unpin;
+
+    /// Returns a `Stream` object for the <K, V> pairs whose packed key is in the half-open range `[from, to)`, ordered
+    /// by packed key.
+    async fn range_stream(&'a self, from: &K, to: &K) -> Result<Self::RangeStream, Self::Error>;
+}
+
+/// `AsPrefixStream<'a, K, V>` trait extends the `StorageBackend` with `prefix_stream` operation for the (key: K, value:
+/// V) pair; therefore, it should be explicitly implemented for the corresponding `StorageBackend`.
+#[async_trait::async_trait]
+pub trait AsPrefixStream<'a, K, V>: StorageBackend {
+    /// Type to iterate through the <K, V> collection with a given key prefix.
+    type PrefixStream: Stream<Item = (K, V)> + Send + Sync + Unpin;
+
+    /// Returns a `Stream` object for the <K, V> pairs whose packed key starts with `prefix`, ordered by packed key.
+    async fn prefix_stream(&'a self, prefix: &[u8]) -> Result<Self::PrefixStream, Self::Error>;
+}
+
+/// `AsReverseStream<'a, K, V>` trait extends the `StorageBackend` with `reverse_stream` operation for the (key: K,
+/// value: V) pair; therefore, it should be explicitly implemented for the corresponding `StorageBackend`.
+#[async_trait::async_trait]
+pub trait AsReverseStream<'a, K, V>: StorageBackend {
+    /// Type to iterate through the <K, V> collection in reverse order.
+    type ReverseStream: Stream<Item = (K, V)> + Send + Sync + Unpin;
+
+    /// Returns a `Stream` object for the provided <K, V> collection, in reverse order of packed key.
+    async fn reverse_stream(&'a self) -> Result<Self::ReverseStream, Self::Error>;
+}
+
+/// `AsKeyStream<'a, K, V>` trait extends the `StorageBackend` with `key_stream` operation for the (key: K, value: V)
+/// pair; therefore, it should be explicitly implemented for the corresponding `StorageBackend`.
+#[async_trait::async_trait]
+pub trait AsKeyStream<'a, K, V>: StorageBackend {
+    /// Type to iterate through the keys of the <K, V> collection.
+    type KeyStream: Stream<Item = K> + Send + Sync + Unpin;
+
+    /// Returns a `Stream` object for the keys of the provided <K, V> collection, ordered by packed key.
+    ///
+    /// Backends may implement it without reading the values, unlike its `StreamFallback` implementation.
+    async fn key_stream(&'a self) -> Result<Self::KeyStream, Self::Error>;
+}
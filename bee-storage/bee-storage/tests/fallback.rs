@@ -0,0 +1,122 @@
+// Copyright 2020-2021 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+use bee_storage::{
+    access::{AsKeyStream, AsPrefixStream, AsRangeStream, AsReverseStream, AsStream, Fetch, StreamFallback},
+    backend::StorageBackend,
+};
+
+use async_trait::async_trait;
+use futures::{
+    executor::block_on,
+    stream::{self, Iter},
+    StreamExt,
+};
+
+use std::{convert::Infallible, vec::IntoIter};
+
+/// A backend that only knows how to fetch its entries and stream them, in insertion order.
+struct Backend(Vec<(u32, u64)>);
+
+#[async_trait]
+impl StorageBackend for Backend {
+    type ConfigBuilder = ();
+    type Config = ();
+    type Error = Infallible;
+
+    async fn start(_: Self::Config) -> Result<Self, Self::Error> {
+        // Packed little-endian, the keys are ordered as 256, 512, 1, 2.
+        Ok(Self(vec![(1, 10), (512, 5120), (2, 20), (256, 2560)]))
+    }
+
+    async fn shutdown(self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+
+    async fn size(&self) -> Result<Option<usize>, Self::Error> {
+        Ok(None)
+    }
+}
+
+#[async_trait]
+impl<'a> AsStream<'a, u32, u64> for Backend {
+    type Stream = Iter<IntoIter<(u32, u64)>>;
+
+    async fn stream(&'a self) -> Result<Self::Stream, Self::Error> {
+        Ok(stream::iter(self.0.clone()))
+    }
+}
+
+#[async_trait]
+impl Fetch<u32, u64> for Backend {
+    async fn fetch(&self, key: &u32) -> Result<Option<u64>, Self::Error> {
+        Ok(self.0.iter().find(|(k, _)| k == key).map(|(_, value)| *value))
+    }
+}
+
+impl StreamFallback for Backend {}
+
+#[test]
+fn range_and_prefix() {
+    block_on(async {
+        let storage = Backend::start(()).await.unwrap();
+
+        let range = AsRangeStream::<u32, u64>::range_stream(&storage, &512, &2)
+            .await
+            .unwrap()
+            .collect::<Vec<_>>()
+            .await;
+        assert_eq!(range, vec![(512, 5120), (1, 10)]);
+
+        let empty = AsRangeStream::<u32, u64>::range_stream(&storage, &2, &512)
+            .await
+            .unwrap()
+            .collect::<Vec<_>>()
+            .await;
+        assert!(empty.is_empty());
+
+        let prefix = AsPrefixStream::<u32, u64>::prefix_stream(&storage, &[0])
+            .await
+            .unwrap()
+            .collect::<Vec<_>>()
+            .await;
+        assert_eq!(prefix, vec![(256, 2560), (512, 5120)]);
+    });
+}
+
+#[test]
+fn reverse_and_keys() {
+    block_on(async {
+        let storage = Backend::start(()).await.unwrap();
+
+        let reverse = AsReverseStream::<u32, u64>::reverse_stream(&storage)
+            .await
+            .unwrap()
+            .collect::<Vec<_>>()
+            .await;
+        assert_eq!(reverse, vec![(2, 20), (1, 10), (512, 5120), (256, 2560)]);
+
+        let keys = AsKeyStream::<u32, u64>::key_stream(&storage)
+            .await
+            .unwrap()
+            .collect::<Vec<_>>()
+            .await;
+        assert_eq!(keys, vec![256, 512, 1, 2]);
+    });
+}
+
+#[test]
+fn multi_fetch() {
+    block_on(async {
+        let storage = Backend::start(()).await.unwrap();
+
+        let values = Fetch::<u32, u64>::multi_fetch(&storage, &[2, 3, 1, 2]).await.unwrap();
+        assert_eq!(values, vec![Some(20), None, Some(10), Some(20)]);
+
+        let keys = Vec::<u32>::new();
+        assert!(Fetch::<u32, u64>::multi_fetch(&storage, &keys)
+            .await
+            .unwrap()
+            .is_empty());
+    });
+}
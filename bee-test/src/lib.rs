@@ -1,4 +1,6 @@
 // Copyright 2020-2021 IOTA Stiftung
 // SPDX-License-Identifier: Apache-2.0
 
+#[cfg(feature = "simulation")]
+pub mod simulation;
 pub mod ternary;
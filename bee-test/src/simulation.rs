@@ -0,0 +1,100 @@
+// Copyright 2020-2021 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+//! A deterministic simulation runtime to test nodes and their workers.
+//!
+//! A [`Simulation`] runs a future on a single-threaded tokio runtime whose clock is virtual: time only advances when
+//! every task is idle, straight to the next timer, so that a test waiting for hours of timers completes immediately.
+//! The scheduling of node tasks is perturbed according to the seed of the simulation, see
+//! [`bee_runtime::simulation`], such that each seed yields a different but reproducible interleaving:
+//! ```ignore
+//! Simulation::from_env().run(async {
+//!     let node = BeeNodeBuilder::<Backend>::new(config)?.with_worker::<Gossip>().finish().await?;
+//!     tokio::time::sleep(Duration::from_secs(3600)).await;
+//!     node.stop().await
+//! });
+//! ```
+//! When a simulation panics, its seed is printed; setting `BEE_SIMULATION_SEED` to it replays the same interleaving.
+
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use tokio::runtime;
+
+use std::{env, future::Future, ops::Range, thread};
+
+/// The environment variable that sets the seed of [`Simulation::from_env`].
+pub const SEED_VAR: &str = "BEE_SIMULATION_SEED";
+
+/// A deterministic simulation runtime with virtual time and a seeded scheduler.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Simulation {
+    seed: u64,
+}
+
+impl Simulation {
+    /// Create a simulation with the given seed.
+    pub fn new(seed: u64) -> Self {
+        Self { seed }
+    }
+
+    /// Create a simulation with the seed set by the `BEE_SIMULATION_SEED` environment variable, or a random one.
+    pub fn from_env() -> Self {
+        match env::var(SEED_VAR) {
+            Ok(seed) => Self::new(
+                seed.parse()
+                    .unwrap_or_else(|_| panic!("{} must be an unsigned integer, not `{}`.", SEED_VAR, seed)),
+            ),
+            Err(_) => Self::new(rand::thread_rng().gen()),
+        }
+    }
+
+    /// Get the seed of the simulation.
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// Get a random number generator seeded by the simulation, for tests to derive their own random data from.
+    pub fn rng(&self) -> StdRng {
+        StdRng::seed_from_u64(self.seed)
+    }
+
+    /// Run a future to completion in the simulation.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the future panics, after printing the seed of the simulation.
+    pub fn run<F: Future>(&self, future: F) -> F::Output {
+        let runtime = runtime::Builder::new_current_thread()
+            .enable_time()
+            .start_paused(true)
+            .build()
+            .expect("Building the simulation runtime failed.");
+
+        let _report = SeedReport(self.seed);
+        let _simulation = bee_runtime::simulation::enter(self.seed);
+
+        runtime.block_on(future)
+    }
+
+    /// Run the futures created by `f` in a simulation for each seed of the given range, e.g. to look for an
+    /// interleaving that fails.
+    pub fn explore<G, F>(seeds: Range<u64>, mut f: G)
+    where
+        G: FnMut() -> F,
+        F: Future,
+    {
+        for seed in seeds {
+            Self::new(seed).run(f());
+        }
+    }
+}
+
+// Prints the seed of a simulation if it panics.
+struct SeedReport(u64);
+
+impl Drop for SeedReport {
+    fn drop(&mut self) {
+        if thread::panicking() {
+            eprintln!("Simulation failed, replay it with {}={}.", SEED_VAR, self.0);
+        }
+    }
+}
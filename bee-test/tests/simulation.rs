@@ -0,0 +1,158 @@
+// Copyright 2020-2021 IOTA Stiftung
+// SPDX-License-Identifier: Apache-2.0
+
+#![cfg(feature = "simulation")]
+
+use bee_runtime::{
+    node::{BeeNode, BeeNodeBuilder, BeeNodeConfig, Node, NodeBuilder},
+    schedule::{self, MissedTickPolicy},
+    worker::Worker,
+};
+use bee_storage::backend::StorageBackend;
+use bee_test::simulation::Simulation;
+
+use async_trait::async_trait;
+use tokio::{task, time::sleep};
+
+use std::{
+    collections::HashSet,
+    convert::Infallible,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+struct Backend;
+
+#[async_trait]
+impl StorageBackend for Backend {
+    type ConfigBuilder = ();
+    type Config = ();
+    type Error = Infallible;
+
+    async fn start(_config: Self::Config) -> Result<Self, Self::Error> {
+        Ok(Self)
+    }
+
+    async fn shutdown(self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+
+    async fn size(&self) -> Result<Option<usize>, Self::Error> {
+        Ok(None)
+    }
+}
+
+type N = BeeNode<Backend>;
+type Log = Arc<Mutex<Vec<&'static str>>>;
+
+struct Ticker;
+
+#[async_trait]
+impl Worker<N> for Ticker {
+    type Config = Log;
+    type Error = Infallible;
+
+    async fn start(node: &mut N, config: Self::Config) -> Result<Self, Self::Error> {
+        schedule::spawn_interval::<Self, _, _, _>(node, Duration::from_secs(60), MissedTickPolicy::Skip, move || {
+            config.lock().unwrap().push("tick");
+            async {}
+        });
+
+        Ok(Self)
+    }
+}
+
+struct Chatty;
+
+#[async_trait]
+impl Worker<N> for Chatty {
+    type Config = Log;
+    type Error = Infallible;
+
+    async fn start(node: &mut N, config: Self::Config) -> Result<Self, Self::Error> {
+        for name in ["a", "b", "c"] {
+            let log = config.clone();
+
+            node.spawn::<Self, _, _>(move |_shutdown| async move {
+                for _ in 0..3 {
+                    log.lock().unwrap().push(name);
+                    task::yield_now().await;
+                }
+            });
+        }
+
+        Ok(Self)
+    }
+}
+
+fn run(seed: u64) -> Vec<&'static str> {
+    let log = Log::default();
+
+    Simulation::new(seed).run({
+        let log = log.clone();
+        async move {
+            let node = BeeNodeBuilder::<Backend>::new(BeeNodeConfig::default())
+                .unwrap()
+                .with_worker_cfg::<Chatty>(log)
+                .finish()
+                .await
+                .unwrap();
+
+            sleep(Duration::from_millis(1)).await;
+            node.stop().await.unwrap();
+        }
+    });
+
+    Arc::try_unwrap(log).unwrap().into_inner().unwrap()
+}
+
+#[test]
+fn virtual_time() {
+    let log = Log::default();
+    let started = Instant::now();
+
+    Simulation::new(0).run({
+        let log = log.clone();
+        async move {
+            let node = BeeNodeBuilder::<Backend>::new(BeeNodeConfig::default())
+                .unwrap()
+                .with_worker_cfg::<Ticker>(log)
+                .finish()
+                .await
+                .unwrap();
+
+            // A day of ticks, plus the immediate one.
+            sleep(Duration::from_secs(24 * 3600 + 1)).await;
+            node.stop().await.unwrap();
+        }
+    });
+
+    assert_eq!(log.lock().unwrap().len(), 24 * 60 + 1);
+    assert!(started.elapsed() < Duration::from_secs(10));
+}
+
+#[test]
+fn reproducible_interleavings() {
+    let interleavings = (0..16)
+        .map(|seed| {
+            let interleaving = run(seed);
+            assert_eq!(interleaving.len(), 9);
+            assert_eq!(run(seed), interleaving);
+            interleaving
+        })
+        .collect::<HashSet<_>>();
+
+    assert!(interleavings.len() > 1);
+}
+
+#[test]
+fn explore_seeds() {
+    let mut runs = 0;
+
+    Simulation::explore(0..4, || {
+        runs += 1;
+        async { sleep(Duration::from_secs(3600)).await }
+    });
+
+    assert_eq!(runs, 4);
+}